@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Instant;
+use lazy_static::lazy_static;
+use serde::Serialize;
+use sysinfo::Disks;
+use crate::list_process::{now_ms, sampler_running};
+
+/// A mounted filesystem. Sizes are bytes like `ProcessInfo.read_bytes`; inode counts are
+/// `None` where the filesystem doesn't track them (e.g. btrfs reports zero) or off unix.
+#[derive(Serialize, Clone)]
+pub struct FilesystemInfo {
+    mount_point: String,
+    /// Block device name as in `/proc/diskstats`, e.g. `sda1`, when backed by one.
+    device: Option<String>,
+    source: String,
+    file_system: String,
+    kind: String,
+    total: u64,
+    used: u64,
+    available: u64,
+    inodes_total: Option<u64>,
+    inodes_used: Option<u64>,
+    inodes_free: Option<u64>,
+    read_only: bool,
+    removable: bool,
+}
+
+/// Throughput of one block device since the previous sample. Rates are `None` on the first
+/// sample; `utilization` is the percentage of wall time the device had IO in flight.
+#[derive(Serialize, Clone)]
+pub struct BlockDevice {
+    name: String,
+    /// Device-mapper name for `dm-*` devices, as listed under `/dev/mapper`.
+    mapper_name: Option<String>,
+    partition: bool,
+    read_bytes_per_sec: Option<f64>,
+    write_bytes_per_sec: Option<f64>,
+    reads_per_sec: Option<f64>,
+    writes_per_sec: Option<f64>,
+    utilization: Option<f64>,
+    /// Average time a completed request spent queued and serviced.
+    await_ms: Option<f64>,
+    in_flight: u64,
+    total_read_bytes: u64,
+    total_written_bytes: u64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct BlockDeviceSample {
+    timestamp_ms: u64,
+    devices: Vec<BlockDevice>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct DiskStats {
+    timestamp_ms: u64,
+    filesystems: Vec<FilesystemInfo>,
+    devices: Vec<BlockDevice>,
+}
+
+/// Cumulative counters of one `/proc/diskstats` line.
+#[derive(Clone, Copy)]
+struct DiskCounters {
+    reads: u64,
+    sectors_read: u64,
+    ms_reading: u64,
+    writes: u64,
+    sectors_written: u64,
+    ms_writing: u64,
+    in_flight: u64,
+    ms_doing_io: u64,
+}
+
+// diskstats always counts 512-byte sectors, whatever the device's real sector size.
+const SECTOR_SIZE: u64 = 512;
+
+lazy_static! {
+    static ref PREVIOUS_COUNTERS: Mutex<Option<(Instant, HashMap<String, DiskCounters>)>> = Mutex::new(None);
+    static ref LAST_DEVICES: Mutex<Option<BlockDeviceSample>> = Mutex::new(None);
+}
+
+#[cfg(unix)]
+fn inode_counts(mount_point: &Path) -> Option<(u64, u64)> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = std::ffi::CString::new(mount_point.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 || stat.f_files == 0 {
+        return None;
+    }
+    Some((stat.f_files as u64, stat.f_ffree as u64))
+}
+
+#[cfg(not(unix))]
+fn inode_counts(_mount_point: &Path) -> Option<(u64, u64)> {
+    None
+}
+
+fn list_filesystems() -> Vec<FilesystemInfo> {
+    let disks = Disks::new_with_refreshed_list();
+    disks
+        .list()
+        .iter()
+        .map(|disk| {
+            let source = disk.name().to_string_lossy().to_string();
+            let inodes = inode_counts(disk.mount_point());
+            // Sources like /dev/mapper/vg-root are symlinks to the kernel name (/dev/dm-0).
+            let device = source.starts_with("/dev/").then(|| {
+                let resolved = std::fs::canonicalize(&source).unwrap_or_else(|_| source.clone().into());
+                resolved.to_string_lossy().trim_start_matches("/dev/").to_string()
+            });
+            FilesystemInfo {
+                mount_point: disk.mount_point().to_string_lossy().to_string(),
+                device,
+                file_system: disk.file_system().to_string_lossy().to_string(),
+                kind: format!("{:?}", disk.kind()),
+                total: disk.total_space(),
+                used: disk.total_space().saturating_sub(disk.available_space()),
+                available: disk.available_space(),
+                inodes_total: inodes.map(|(total, _)| total),
+                inodes_used: inodes.map(|(total, free)| total.saturating_sub(free)),
+                inodes_free: inodes.map(|(_, free)| free),
+                read_only: disk.is_read_only(),
+                removable: disk.is_removable(),
+                source,
+            }
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn read_diskstats() -> Vec<(String, DiskCounters)> {
+    let Ok(contents) = std::fs::read_to_string("/proc/diskstats") else {
+        return vec![];
+    };
+    contents
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            // Fields from the 4th on are counters; see Documentation/admin-guide/iostats.rst.
+            let counter = |n: usize| fields.get(n).and_then(|v| v.parse::<u64>().ok());
+            Some((fields.get(2)?.to_string(), DiskCounters {
+                reads: counter(3)?,
+                sectors_read: counter(5)?,
+                ms_reading: counter(6)?,
+                writes: counter(7)?,
+                sectors_written: counter(9)?,
+                ms_writing: counter(10)?,
+                in_flight: counter(11)?,
+                ms_doing_io: counter(12)?,
+            }))
+        })
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_diskstats() -> Vec<(String, DiskCounters)> {
+    vec![]
+}
+
+fn block_device(name: String, now: &DiskCounters, before: Option<&DiskCounters>, secs: f64) -> BlockDevice {
+    let rate = |now: u64, before: u64| now.saturating_sub(before) as f64 / secs;
+    let before = before.filter(|_| secs > 0.0);
+
+    BlockDevice {
+        mapper_name: std::fs::read_to_string(format!("/sys/block/{}/dm/name", name))
+            .ok()
+            .map(|n| n.trim_end().to_string()),
+        // Whole disks have a directory in /sys/block; partitions only live under their disk.
+        partition: !Path::new("/sys/block").join(name.replace('/', "!")).exists(),
+        read_bytes_per_sec: before.map(|b| rate(now.sectors_read, b.sectors_read) * SECTOR_SIZE as f64),
+        write_bytes_per_sec: before.map(|b| rate(now.sectors_written, b.sectors_written) * SECTOR_SIZE as f64),
+        reads_per_sec: before.map(|b| rate(now.reads, b.reads)),
+        writes_per_sec: before.map(|b| rate(now.writes, b.writes)),
+        utilization: before.map(|b| (rate(now.ms_doing_io, b.ms_doing_io) / 10.0).min(100.0)),
+        await_ms: before.and_then(|b| {
+            let ios = (now.reads + now.writes).saturating_sub(b.reads + b.writes);
+            let ms = (now.ms_reading + now.ms_writing).saturating_sub(b.ms_reading + b.ms_writing);
+            (ios > 0).then(|| ms as f64 / ios as f64)
+        }),
+        in_flight: now.in_flight,
+        total_read_bytes: now.sectors_read * SECTOR_SIZE,
+        total_written_bytes: now.sectors_written * SECTOR_SIZE,
+        name,
+    }
+}
+
+/// Reads `/proc/diskstats` and advances the rate baseline. Devices that have never done IO
+/// (unused loop and ram devices) are left out.
+pub(crate) fn sample_block_devices() -> BlockDeviceSample {
+    let at = Instant::now();
+    let counters = read_diskstats();
+
+    let mut previous = PREVIOUS_COUNTERS.lock().unwrap();
+    let (secs, before) = match previous.as_ref() {
+        Some((then, before)) => (at.duration_since(*then).as_secs_f64(), Some(before)),
+        None => (0.0, None),
+    };
+    let devices = counters
+        .iter()
+        .filter(|(_, c)| c.reads + c.writes > 0)
+        .map(|(name, now)| block_device(name.clone(), now, before.and_then(|b| b.get(name)), secs))
+        .collect();
+    *previous = Some((at, counters.into_iter().collect()));
+
+    let sample = BlockDeviceSample { timestamp_ms: now_ms(), devices };
+    *LAST_DEVICES.lock().unwrap() = Some(sample.clone());
+    sample
+}
+
+/// Mounted filesystems and block device throughput. Filesystem usage is always read fresh;
+/// only the device rates depend on a previous sample.
+#[tauri::command]
+pub async fn disk_stats() -> Result<DiskStats, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        let cached = if sampler_running() { LAST_DEVICES.lock().unwrap().clone() } else { None };
+        let sample = cached.unwrap_or_else(sample_block_devices);
+        DiskStats {
+            timestamp_ms: sample.timestamp_ms,
+            filesystems: list_filesystems(),
+            devices: sample.devices,
+        }
+    }).await.map_err(|e| e.to_string())
+}
@@ -0,0 +1,166 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use lazy_static::lazy_static;
+use serde::Serialize;
+use sysinfo::System;
+use crate::list_process::{thread_ids, ProcessInfo};
+
+/// Samples kept per PID: an hour at the default 2 s sampler interval.
+const HISTORY_CAPACITY: usize = 1800;
+/// How long the series of an exited process is kept after its last sample.
+const EXITED_RETENTION_MS: u64 = 60 * 60 * 1000;
+const DEFAULT_MAX_POINTS: usize = 300;
+
+/// One sampler tick for one process. Memory is KiB like `ProcessInfo.memory`; the IO rates are
+/// bytes per second. Floats are `f32` to keep the ring buffers small.
+#[derive(Serialize, Clone, Copy)]
+pub struct HistoryPoint {
+    timestamp_ms: u64,
+    cpu: f32,
+    memory: f32,
+    read_rate: f32,
+    write_rate: f32,
+    threads: u32,
+}
+
+struct ProcessSeries {
+    name: String,
+    /// Derived from uptime; a different start time under the same PID means it was reused.
+    start_secs: u64,
+    points: VecDeque<HistoryPoint>,
+}
+
+#[derive(Serialize)]
+pub struct ProcessHistory {
+    pid: u32,
+    name: String,
+    /// Whether the process was present in the latest sample.
+    alive: bool,
+    /// Span of time each point covers: the sampler interval, or the bucket width after
+    /// downsampling.
+    resolution_ms: u64,
+    points: Vec<HistoryPoint>,
+}
+
+lazy_static! {
+    static ref HISTORY: Mutex<HashMap<u32, ProcessSeries>> = Mutex::new(HashMap::new());
+    static ref LAST_RECORDED_MS: Mutex<u64> = Mutex::new(0);
+}
+
+/// Appends the current sample of every process (not every thread) to its ring buffer.
+pub(crate) fn record(sys: &System, processes: &[ProcessInfo], timestamp_ms: u64, interval_ms: u64) {
+    let threads = thread_ids(sys);
+    let now_secs = timestamp_ms / 1000;
+
+    let mut history = HISTORY.lock().unwrap();
+    for process in processes.iter().filter(|p| !threads.contains(&p.pid)) {
+        let start_secs = now_secs.saturating_sub(process.uptime);
+        let series = history.entry(process.pid).or_insert_with(|| ProcessSeries {
+            name: process.name.clone(),
+            start_secs,
+            points: VecDeque::new(),
+        });
+        // Allow for the second boundary falling between uptime and timestamp.
+        if series.start_secs.abs_diff(start_secs) > 1 {
+            series.start_secs = start_secs;
+            series.points.clear();
+        }
+        series.name = process.name.clone();
+
+        // `read_bytes`/`written_bytes` count since the previous refresh.
+        let elapsed_ms = series
+            .points
+            .back()
+            .map(|p| timestamp_ms.saturating_sub(p.timestamp_ms))
+            .filter(|ms| *ms > 0)
+            .unwrap_or(interval_ms)
+            .max(1);
+        let per_sec = |bytes: u64| (bytes as f64 * 1000.0 / elapsed_ms as f64) as f32;
+
+        if series.points.len() == HISTORY_CAPACITY {
+            series.points.pop_front();
+        }
+        series.points.push_back(HistoryPoint {
+            timestamp_ms,
+            cpu: process.cpu,
+            memory: process.memory as f32,
+            read_rate: per_sec(process.read_bytes),
+            write_rate: per_sec(process.written_bytes),
+            threads: process.num_threads.unwrap_or(1),
+        });
+    }
+
+    history.retain(|_, series| {
+        series.points.back().is_some_and(|p| timestamp_ms.saturating_sub(p.timestamp_ms) < EXITED_RETENTION_MS)
+    });
+    *LAST_RECORDED_MS.lock().unwrap() = timestamp_ms;
+}
+
+/// Averages `points` into buckets of `bucket_ms`, keyed by the bucket's start time.
+fn downsample(points: &[HistoryPoint], bucket_ms: u64) -> Vec<HistoryPoint> {
+    let mut buckets: Vec<(HistoryPoint, usize)> = vec![];
+    for point in points {
+        let start = point.timestamp_ms - point.timestamp_ms % bucket_ms;
+        match buckets.last_mut() {
+            Some((sum, count)) if sum.timestamp_ms == start => {
+                sum.cpu += point.cpu;
+                sum.memory += point.memory;
+                sum.read_rate += point.read_rate;
+                sum.write_rate += point.write_rate;
+                sum.threads += point.threads;
+                *count += 1;
+            }
+            _ => buckets.push((HistoryPoint { timestamp_ms: start, ..*point }, 1)),
+        }
+    }
+
+    buckets
+        .into_iter()
+        .map(|(sum, count)| HistoryPoint {
+            timestamp_ms: sum.timestamp_ms,
+            cpu: sum.cpu / count as f32,
+            memory: sum.memory / count as f32,
+            read_rate: sum.read_rate / count as f32,
+            write_rate: sum.write_rate / count as f32,
+            threads: sum.threads / count as u32,
+        })
+        .collect()
+}
+
+/// The last `window_secs` (default: everything kept) of a process's samples, averaged down to
+/// at most `max_points` (default 300). History is recorded while the process sampler runs and
+/// outlives the frontend, so a reloaded window can redraw its charts.
+#[tauri::command]
+pub fn process_history(pid: u32, window_secs: Option<u64>, max_points: Option<usize>) -> Result<ProcessHistory, String> {
+    let history = HISTORY.lock().unwrap();
+    let series = history.get(&pid).ok_or_else(|| format!("No history for PID {}", pid))?;
+    let last_recorded = *LAST_RECORDED_MS.lock().unwrap();
+
+    let newest = series.points.back().map(|p| p.timestamp_ms).unwrap_or(0);
+    let since = window_secs.map(|secs| newest.saturating_sub(secs * 1000)).unwrap_or(0);
+    let points: Vec<HistoryPoint> = series.points.iter().filter(|p| p.timestamp_ms >= since).copied().collect();
+
+    let resolution_ms = match (points.first(), points.last()) {
+        (Some(first), Some(last)) if points.len() > 1 => {
+            (last.timestamp_ms - first.timestamp_ms) / (points.len() as u64 - 1)
+        }
+        _ => 0,
+    };
+    let max_points = max_points.unwrap_or(DEFAULT_MAX_POINTS).max(1);
+    let (points, resolution_ms) = if points.len() > max_points {
+        let span = points.last().unwrap().timestamp_ms - points[0].timestamp_ms;
+        // Bucket alignment can add one partial bucket at each end.
+        let bucket_ms = span.div_ceil(max_points.saturating_sub(2).max(1) as u64).max(1);
+        (downsample(&points, bucket_ms), bucket_ms)
+    } else {
+        (points, resolution_ms)
+    };
+
+    Ok(ProcessHistory {
+        pid,
+        name: series.name.clone(),
+        alive: newest == last_recorded,
+        resolution_ms,
+        points,
+    })
+}
@@ -3,7 +3,11 @@ use tauri::command;
 use std::{thread, time::Duration};
 use rayon::prelude::*;
 use std::sync::Mutex;
-use crate::list_process::emit_log;
+use crate::list_process::{build_children_index, emit_log};
+use std::collections::{HashMap, HashSet};
+#[cfg(target_os = "linux")]
+use std::collections::BTreeMap;
+use serde::Serialize;
 
 #[tauri::command]
 pub fn kill_process(pid: u32, kill_children: bool, timeout_secs: u64,app_handle: tauri::AppHandle) -> Result<(), String> {
@@ -15,7 +19,8 @@ pub fn kill_process(pid: u32, kill_children: bool, timeout_secs: u64,app_handle:
         let mut descendants = vec![];
 
         if kill_children {
-            collect_descendants_with_depth(target_pid, &sys, &mut descendants, 1,app_handle.clone());
+            let children = build_children_index(&sys);
+            collect_descendants_with_depth(target_pid, &children, &mut descendants, 1,app_handle.clone());
             descendants.sort_by(|a, b| b.1.cmp(&a.1)); // Deepest first
         }
 
@@ -44,18 +49,30 @@ pub fn kill_process(pid: u32, kill_children: bool, timeout_secs: u64,app_handle:
 
 pub(crate) fn collect_descendants_with_depth(
     pid: Pid,
-    sys: &System,
+    children: &HashMap<Pid, Vec<Pid>>,
     collected: &mut Vec<(Pid, usize)>,
     depth: usize,
-    app_handle: tauri::AppHandle
+    _app_handle: tauri::AppHandle
+) {
+    let mut visited: HashSet<Pid> = collected.iter().map(|(p, _)| *p).collect();
+    visited.insert(pid);
+    collect_unvisited(pid, children, collected, &mut visited, depth);
+}
+
+fn collect_unvisited(
+    pid: Pid,
+    children: &HashMap<Pid, Vec<Pid>>,
+    collected: &mut Vec<(Pid, usize)>,
+    visited: &mut HashSet<Pid>,
+    depth: usize,
 ) {
-    for (cpid, proc) in sys.processes() {
-        if let Some(parent) = proc.parent() {
-            if parent == pid {
-                collected.push((*cpid, depth));
-                collect_descendants_with_depth(*cpid, sys, collected, depth + 1,app_handle.clone());
-            }
+    for cpid in children.get(&pid).map(Vec::as_slice).unwrap_or(&[]) {
+        // A reused PID can make the parent map cyclic; never queue the same process twice.
+        if !visited.insert(*cpid) {
+            continue;
         }
+        collected.push((*cpid, depth));
+        collect_unvisited(*cpid, children, collected, visited, depth + 1);
     }
 }
 
@@ -140,7 +157,8 @@ pub fn batch_kill_processes(
         let mut descendants = vec![];
 
         if kill_children {
-            collect_descendants_with_depth(target_pid, &sys, &mut descendants, 1,app_handle.clone());
+            let children = build_children_index(&sys);
+            collect_descendants_with_depth(target_pid, &children, &mut descendants, 1,app_handle.clone());
             descendants.sort_by(|a, b| b.1.cmp(&a.1));
         }
 
@@ -159,3 +177,96 @@ pub fn batch_kill_processes(
     });
     Ok(())
 }
+
+#[derive(Serialize, Clone)]
+pub struct PortHolder {
+    pid: u32,
+    name: String,
+    sockets: Vec<crate::sockets::SocketInfo>,
+}
+
+/// Processes with a TCP/UDP socket bound to local `port`, grouped by PID. `proto` is
+/// `"tcp"`, `"udp"` or `None` for both; IPv6 sockets are always included.
+#[cfg(target_os = "linux")]
+fn port_holders(port: u16, proto: Option<&str>) -> Result<Vec<PortHolder>, String> {
+    if let Some(proto) = proto {
+        if proto != "tcp" && proto != "udp" {
+            return Err(format!("Unknown protocol {:?}, expected \"tcp\" or \"udp\"", proto));
+        }
+    }
+
+    let mut sockets: Vec<_> = crate::sockets::read_inet_sockets()
+        .into_iter()
+        .filter(|s| s.local_port == Some(port))
+        .filter(|s| proto.is_none_or(|p| s.protocol.starts_with(p)))
+        .collect();
+    crate::sockets::attach_owners(&mut sockets, &crate::sockets::socket_owners());
+
+    let mut holders: BTreeMap<u32, PortHolder> = BTreeMap::new();
+    for socket in sockets {
+        for owner in socket.owners.clone() {
+            holders
+                .entry(owner.pid)
+                .or_insert_with(|| PortHolder { pid: owner.pid, name: owner.name.clone(), sockets: vec![] })
+                .sockets
+                .push(socket.clone());
+        }
+    }
+    Ok(holders.into_values().collect())
+}
+
+/// Lists what [`kill_by_port`] would kill, without sending any signal.
+#[command]
+pub fn preview_kill_by_port(port: u16, proto: Option<String>) -> Result<Vec<PortHolder>, String> {
+    #[cfg(target_os = "linux")]
+    {
+        port_holders(port, proto.as_deref())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (port, proto);
+        Err("Unsupported platform".to_string())
+    }
+}
+
+/// Sends every process holding `port` through the usual SIGTERM -> SIGKILL escalation.
+/// Returns the targeted PIDs; progress arrives through `log` events.
+#[command]
+pub fn kill_by_port(
+    port: u16,
+    proto: Option<String>,
+    timeout_secs: u64,
+    app_handle: tauri::AppHandle
+) -> Result<Vec<u32>, String> {
+    #[cfg(target_os = "linux")]
+    {
+        let own_pid = std::process::id();
+        let pids: Vec<u32> = port_holders(port, proto.as_deref())?
+            .into_iter()
+            .map(|h| h.pid)
+            .filter(|pid| *pid != own_pid)
+            .collect();
+        if pids.is_empty() {
+            return Err(format!("No process holds port {}", port));
+        }
+
+        let targets = pids.clone();
+        std::thread::spawn(move || {
+            let results: Vec<String> = targets
+                .par_iter()
+                .map(|pid| try_graceful_kill(Pid::from_u32(*pid), timeout_secs, None, app_handle.clone()))
+                .collect();
+            emit_log(app_handle.clone(), format!("📝 Kill results for port {}:\n{}", port, results.join("\n")));
+            println!("📝 Kill results for port {}:\n{}", port, results.join("\n"));
+        });
+
+        Ok(pids)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (port, proto, timeout_secs, app_handle);
+        Err("Unsupported platform".to_string())
+    }
+}
@@ -1,11 +1,44 @@
 use std::ptr;
 use tauri::Manager;
 use crate::kill_process::kill_process;
-use crate::list_process::{auto_respawn, batch_set_priority, kill_and_restart, list_processes, pid_to_proc, restart_process, stop_auto_respawn};
+use crate::list_process::{auto_respawn, batch_set_priority, kill_and_restart, launch_spec, list_processes, pid_to_proc, process_tree, query_processes, restart_process, resync_processes, set_sample_interval, set_sample_pss, set_sample_view, stop_auto_respawn, subscribe_processes, unsubscribe_processes};
 use crate::list_process::set_process_priority;
-use crate::kill_process::batch_kill_processes;
+use crate::kill_process::{batch_kill_processes, kill_by_port, preview_kill_by_port};
+use crate::list_threads::list_threads;
+use crate::open_files::list_open_files;
+use crate::sockets::{listening_ports, process_sockets};
+use crate::memory_info::memory_details;
+use crate::memory_maps::{memory_maps, processes_mapping};
+use crate::stale_binaries::scan_stale_processes;
+use crate::process_details::process_details;
+use crate::system_overview::system_overview;
+use crate::pressure::system_pressure;
+use crate::disks::disk_stats;
+use crate::network::network_stats;
+use crate::history::process_history;
+use crate::metrics_store::{metrics_at, metrics_store_config, query_metrics, set_metrics_store_config};
+use crate::lifecycle::process_timeline;
+use crate::proc_connector::set_proc_connector;
 mod list_process;
 mod kill_process;
+mod list_threads;
+mod open_files;
+mod sockets;
+mod memory_info;
+mod memory_maps;
+mod stale_binaries;
+mod process_details;
+mod query;
+mod system_overview;
+mod pressure;
+mod disks;
+mod network;
+mod history;
+mod metrics_store;
+mod lifecycle;
+mod proc_connector;
+#[cfg(target_os = "linux")]
+mod procfs;
 
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
@@ -30,6 +63,7 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .invoke_handler(tauri::generate_handler![
             list_processes,
+            query_processes,
             batch_kill_processes,
             set_process_priority,
             kill_process,
@@ -39,7 +73,37 @@ pub fn run() {
             stop_auto_respawn,
             batch_set_priority,
             set_process_limits,
-            pid_to_proc
+            pid_to_proc,
+            process_details,
+            process_sockets,
+            listening_ports,
+            preview_kill_by_port,
+            kill_by_port,
+            subscribe_processes,
+            unsubscribe_processes,
+            set_sample_interval,
+            resync_processes,
+            process_tree,
+            launch_spec,
+            list_threads,
+            list_open_files,
+            memory_details,
+            set_sample_pss,
+            set_sample_view,
+            memory_maps,
+            processes_mapping,
+            scan_stale_processes,
+            system_overview,
+            system_pressure,
+            disk_stats,
+            network_stats,
+            process_history,
+            query_metrics,
+            metrics_at,
+            metrics_store_config,
+            set_metrics_store_config,
+            process_timeline,
+            set_proc_connector
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,295 @@
+//! `process_started` / `process_exited` events from successive sampler snapshots, or from the
+//! kernel proc connector when it is enabled, also appended to a daily `lifecycle-<day>.jsonl`
+//! timeline under the app data directory.
+
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sysinfo::System;
+use tauri::{Emitter, Manager};
+use crate::list_process::{thread_ids, ProcessInfo};
+use crate::query::Query;
+
+const TIMELINE_SEGMENT_MS: u64 = 24 * 60 * 60 * 1000;
+const TIMELINE_RETENTION_DAYS: u64 = 7;
+
+/// `exec`, `uid_changed` and `comm_changed` only come from the proc connector.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum LifecycleKind {
+    Started,
+    Exited,
+    Exec,
+    UidChanged,
+    CommChanged,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum EventSource {
+    #[default]
+    Poll,
+    Netlink,
+}
+
+/// A process appearing, disappearing or changing. From polling, the usage figures of `exited`
+/// are the last ones seen and the exit happened between that sample and `timestamp_ms`. The proc
+/// connector reports events as they happen but only knows usage for processes that were polled.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LifecycleEvent {
+    pub(crate) kind: LifecycleKind,
+    #[serde(default)]
+    pub(crate) source: EventSource,
+    pub(crate) timestamp_ms: u64,
+    pub(crate) pid: u32,
+    pub(crate) name: String,
+    pub(crate) cmd: String,
+    pub(crate) exe: String,
+    pub(crate) parent_pid: Option<u32>,
+    pub(crate) user: Option<String>,
+    pub(crate) runtime_secs: Option<u64>,
+    pub(crate) cpu: Option<f32>,
+    pub(crate) memory: Option<f64>,
+    pub(crate) total_read_bytes: Option<u64>,
+    pub(crate) total_written_bytes: Option<u64>,
+    pub(crate) num_threads: Option<u32>,
+    /// Connector `exited` only; a process killed by a signal has `signal` set instead.
+    pub(crate) exit_code: Option<i32>,
+    pub(crate) signal: Option<i32>,
+    /// Connector `uid_changed` only: the new real and effective UIDs.
+    pub(crate) uid: Option<u32>,
+    pub(crate) euid: Option<u32>,
+}
+
+impl LifecycleEvent {
+    pub(crate) fn new(kind: LifecycleKind, timestamp_ms: u64, process: &ProcessInfo) -> Self {
+        LifecycleEvent {
+            kind,
+            source: EventSource::Poll,
+            timestamp_ms,
+            pid: process.pid,
+            name: process.name.clone(),
+            cmd: process.cmd.clone(),
+            exe: process.exe.clone(),
+            parent_pid: process.parent_pid,
+            user: process.user.clone(),
+            runtime_secs: Some(process.uptime),
+            cpu: Some(process.cpu),
+            memory: Some(process.memory),
+            total_read_bytes: Some(process.total_read_bytes),
+            total_written_bytes: Some(process.total_written_bytes),
+            num_threads: process.num_threads,
+            exit_code: None,
+            signal: None,
+            uid: None,
+            euid: None,
+        }
+    }
+}
+
+/// Set while the proc connector is running; polling then only keeps its baseline so events
+/// aren't reported twice.
+pub(crate) static NETLINK_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+struct Tracker {
+    /// Processes of the previous sample with their derived start time; `None` until the
+    /// first sample, which only sets the baseline.
+    known: Option<HashMap<u32, (u64, ProcessInfo)>>,
+    timeline_dir: Option<PathBuf>,
+    last_prune_segment: u64,
+}
+
+lazy_static! {
+    static ref TRACKER: Mutex<Tracker> = Mutex::new(Tracker {
+        known: None,
+        timeline_dir: None,
+        last_prune_segment: 0,
+    });
+}
+
+/// Diffs `processes` against the previous sample. The events are published by [`publish`]
+/// once SYS is unlocked.
+pub(crate) fn observe(sys: &System, processes: &[ProcessInfo], timestamp_ms: u64) -> Vec<LifecycleEvent> {
+    let threads = thread_ids(sys);
+    let now_secs = timestamp_ms / 1000;
+    let current: HashMap<u32, (u64, ProcessInfo)> = processes
+        .iter()
+        .filter(|p| !threads.contains(&p.pid))
+        .map(|p| (p.pid, (now_secs.saturating_sub(p.uptime), p.clone())))
+        .collect();
+
+    let mut tracker = TRACKER.lock().unwrap();
+    let Some(known) = tracker.known.replace(current) else {
+        return vec![];
+    };
+    if NETLINK_ACTIVE.load(Ordering::Relaxed) {
+        return vec![];
+    }
+    let current = tracker.known.as_ref().unwrap();
+
+    // A start time that moved by more than rounding means the PID was reused.
+    let same = |a: u64, b: u64| a.abs_diff(b) <= 1;
+    let mut events = vec![];
+    for (pid, (started, process)) in &known {
+        if !current.get(pid).is_some_and(|(now, _)| same(*now, *started)) {
+            events.push(LifecycleEvent::new(LifecycleKind::Exited, timestamp_ms, process));
+        }
+    }
+    for (pid, (started, process)) in current {
+        if !known.get(pid).is_some_and(|(before, _)| same(*before, *started)) {
+            events.push(LifecycleEvent::new(LifecycleKind::Started, timestamp_ms, process));
+        }
+    }
+    events
+}
+
+/// The process as of the latest poll, for events the proc connector reports after it is gone.
+#[cfg(target_os = "linux")]
+pub(crate) fn last_known(pid: u32) -> Option<ProcessInfo> {
+    TRACKER.lock().unwrap().known.as_ref()?.get(&pid).map(|(_, process)| process.clone())
+}
+
+/// Forgets the baseline so a restarted sampler doesn't report everything that changed while
+/// it was stopped. Called when the sampler starts.
+pub(crate) fn reset() {
+    TRACKER.lock().unwrap().known = None;
+}
+
+impl Tracker {
+    fn timeline_dir(&mut self, app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+        if let Some(dir) = &self.timeline_dir {
+            return Ok(dir.clone());
+        }
+        let dir = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("Failed to resolve app data directory: {}", e))?
+            .join("timeline");
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        self.timeline_dir = Some(dir.clone());
+        Ok(dir)
+    }
+
+    fn prune(&mut self, dir: &Path, now_ms: u64) {
+        let segment = now_ms / TIMELINE_SEGMENT_MS;
+        if segment == self.last_prune_segment {
+            return;
+        }
+        self.last_prune_segment = segment;
+
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let index = parse_segment_name(&name);
+            if index.is_some_and(|index| index.saturating_add(TIMELINE_RETENTION_DAYS) < segment) {
+                if let Err(e) = fs::remove_file(entry.path()) {
+                    println!("Failed to remove timeline segment {}: {}", name, e);
+                }
+            }
+        }
+    }
+}
+
+/// The day index of a `lifecycle-<day>.jsonl` segment.
+fn parse_segment_name(name: &str) -> Option<u64> {
+    name.strip_prefix("lifecycle-")?.strip_suffix(".jsonl")?.parse().ok()
+}
+
+/// Emits each event and appends it to the timeline.
+pub(crate) fn publish(app_handle: &tauri::AppHandle, events: &[LifecycleEvent]) -> Result<(), String> {
+    let mut lines = String::new();
+    for event in events {
+        let name = match event.kind {
+            LifecycleKind::Started => "process_started",
+            LifecycleKind::Exited => "process_exited",
+            LifecycleKind::Exec => "process_exec",
+            LifecycleKind::UidChanged => "process_uid_changed",
+            LifecycleKind::CommChanged => "process_comm_changed",
+        };
+        if let Err(e) = app_handle.emit(name, event) {
+            println!("Failed to emit {}: {}", name, e);
+        }
+        lines.push_str(&serde_json::to_string(event).map_err(|e| e.to_string())?);
+        lines.push('\n');
+    }
+    let Some(first) = events.first() else {
+        return Ok(());
+    };
+
+    let mut tracker = TRACKER.lock().unwrap();
+    let dir = tracker.timeline_dir(app_handle)?;
+    let path = dir.join(format!("lifecycle-{}.jsonl", first.timestamp_ms / TIMELINE_SEGMENT_MS));
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(lines.as_bytes()))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    tracker.prune(&dir, first.timestamp_ms);
+    Ok(())
+}
+
+/// Lifecycle events between two Unix timestamps in milliseconds, oldest first. `filter` uses the
+/// process query language over event fields, e.g. `kind == "exited" && name ~ /nginx/`. With
+/// `limit`, only the most recent events are returned. Events are recorded while the process
+/// sampler runs and kept for a week.
+#[tauri::command]
+pub async fn process_timeline(
+    app_handle: tauri::AppHandle,
+    from_ms: u64,
+    to_ms: u64,
+    filter: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<LifecycleEvent>, String> {
+    let query = filter.as_deref().map(Query::parse).transpose().map_err(|e| e.to_string())?;
+    let dir = TRACKER.lock().unwrap().timeline_dir(&app_handle)?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        // Only segments that exist are visited, however wide the requested range.
+        let days = from_ms / TIMELINE_SEGMENT_MS..=to_ms / TIMELINE_SEGMENT_MS;
+        let mut segments: Vec<(u64, PathBuf)> = fs::read_dir(&dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| {
+                let index = parse_segment_name(&entry.file_name().to_string_lossy())?;
+                days.contains(&index).then(|| (index, entry.path()))
+            })
+            .collect();
+        segments.sort();
+
+        let mut events = vec![];
+        for (_, path) in segments {
+            let Ok(contents) = fs::read_to_string(path) else {
+                continue;
+            };
+            for event in contents.lines().filter_map(|line| serde_json::from_str::<LifecycleEvent>(line).ok()) {
+                if !(from_ms..=to_ms).contains(&event.timestamp_ms) {
+                    continue;
+                }
+                if let Some(query) = &query {
+                    let Ok(Value::Object(fields)) = serde_json::to_value(&event) else {
+                        continue;
+                    };
+                    query.check_fields(&fields).map_err(|e| e.to_string())?;
+                    if !query.matches(&fields) {
+                        continue;
+                    }
+                }
+                events.push(event);
+            }
+        }
+        if let Some(limit) = limit {
+            events.drain(..events.len().saturating_sub(limit));
+        }
+        Ok(events)
+    }).await.map_err(|e| e.to_string())?
+}
@@ -1,78 +1,935 @@
+use std::ffi::{OsStr, OsString};
 use std::path::Path;
 use std::process::{Child, Command};
 use std::sync::{Arc, Mutex};
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::{panic, thread};
-use std::collections::HashMap;
-use std::time::{Duration, Instant};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use lazy_static::lazy_static;
-use serde::Serialize;
-use sysinfo::{System, Pid, ProcessesToUpdate, DiskUsage};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use crate::query::{Query, QueryError};
+use sysinfo::{System, Pid, ProcessesToUpdate, ProcessRefreshKind, UpdateKind, DiskUsage, Users, Groups, Uid, Gid};
 
-#[derive(Serialize)]
+/// `name`, `cmd` and `exe` are lossy UTF-8 for display. The `*_raw` fields keep the original
+/// bytes in the escaped form produced by [`escape_os_str`], so argv survives a round trip.
+#[derive(Serialize, Clone, Default)]
 pub struct ProcessInfo {
-    pid: u32,
-    name: String,
-    cmd: String,
-    cpu: f32,
-    memory: f64,
-    uptime: u64,
-    parent_pid: Option<u32>,
-    children: Vec<u32>,
-    exe: String,
-    read_bytes:u64,
-    written_bytes:u64,
-    total_read_bytes:u64,
-    total_written_bytes:u64,
+    pub(crate) pid: u32,
+    pub(crate) name: String,
+    pub(crate) name_raw: String,
+    pub(crate) cmd: String,
+    pub(crate) cmd_raw: Vec<String>,
+    pub(crate) cpu: f32,
+    pub(crate) memory: f64,
+    pub(crate) uptime: u64,
+    pub(crate) parent_pid: Option<u32>,
+    pub(crate) children: Vec<u32>,
+    pub(crate) exe: String,
+    pub(crate) exe_raw: String,
+    pub(crate) read_bytes:u64,
+    pub(crate) written_bytes:u64,
+    pub(crate) total_read_bytes:u64,
+    pub(crate) total_written_bytes:u64,
+    pub(crate) uid: Option<u32>,
+    pub(crate) euid: Option<u32>,
+    pub(crate) gid: Option<u32>,
+    pub(crate) egid: Option<u32>,
+    pub(crate) user: Option<String>,
+    pub(crate) group: Option<String>,
+    pub(crate) session_id: Option<u32>,
+    pub(crate) pgid: Option<u32>,
+    pub(crate) tty: Option<String>,
+    pub(crate) state: String,
+    pub(crate) num_threads: Option<u32>,
+    pub(crate) voluntary_ctxt_switches: Option<u64>,
+    pub(crate) involuntary_ctxt_switches: Option<u64>,
+    pub(crate) minor_faults: Option<u64>,
+    pub(crate) major_faults: Option<u64>,
+    // KiB like `memory`; only filled when PSS sampling is requested since it walks every mapping.
+    pub(crate) pss: Option<f64>,
+}
+
+/// Optional server-side filtering, ordering, paging and projection for [`list_processes`].
+#[derive(Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub struct ProcessListOptions {
+    /// User name or numeric UID; matches the real or effective owner.
+    owner: Option<String>,
+    sort_by_owner: bool,
+    include_pss: bool,
+    /// Any `ProcessInfo` field, e.g. `cpu`. Takes precedence over `sort_by_owner`.
+    sort_key: Option<String>,
+    direction: SortDirection,
+    offset: usize,
+    limit: Option<usize>,
+    /// `ProcessInfo` fields to return; `pid` is always included. All fields when unset.
+    fields: Option<Vec<String>>,
+}
+
+#[derive(Deserialize, Default, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    #[default]
+    Asc,
+    Desc,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ProcessSnapshot {
+    seq: u64,
+    timestamp_ms: u64,
+    interval_ms: u64,
+    processes: Vec<Value>,
+}
+
+/// Changes since the snapshot numbered `base_seq`. `changed` entries carry the `pid` plus
+/// only the fields whose value differs from the previous tick.
+#[derive(Serialize, Clone)]
+pub struct ProcessDelta {
+    seq: u64,
+    base_seq: u64,
+    timestamp_ms: u64,
+    interval_ms: u64,
+    added: Vec<Value>,
+    removed: Vec<u32>,
+    changed: Vec<Map<String, Value>>,
+}
+
+const DEFAULT_SAMPLE_INTERVAL_MS: u64 = 2000;
+const MIN_SAMPLE_INTERVAL_MS: u64 = 250;
+
+enum SamplerMsg {
+    Interval(u64),
+    View(ProcessListOptions),
+    Stop,
+}
+
+struct Sampler {
+    interval_ms: u64,
+    /// Shapes the rows carried by `process_snapshot` and `process_delta` events.
+    view: ProcessListOptions,
+    next_subscription: u64,
+    subscribers: HashSet<u64>,
+    control: Option<Sender<SamplerMsg>>,
 }
 
 lazy_static::lazy_static! {
     static ref SYS: Arc<Mutex<System>> = Arc::new(Mutex::new(System::new_all()));
+    static ref SAMPLER: Mutex<Sampler> = Mutex::new(Sampler {
+        interval_ms: DEFAULT_SAMPLE_INTERVAL_MS,
+        view: ProcessListOptions::default(),
+        next_subscription: 1,
+        subscribers: HashSet::new(),
+        control: None,
+    });
+    static ref LAST_SNAPSHOT: Mutex<Option<ProcessSnapshot>> = Mutex::new(None);
+    // Unshaped copy of the latest sample, served to `current_processes`.
+    static ref LAST_PROCESSES: Mutex<Option<Vec<ProcessInfo>>> = Mutex::new(None);
+    static ref CHILDREN: Mutex<Arc<HashMap<Pid, Vec<Pid>>>> = Mutex::new(Arc::new(HashMap::new()));
+    static ref OWNERS: Mutex<OwnerNames> = Mutex::new(OwnerNames {
+        users: Users::new_with_refreshed_list(),
+        groups: Groups::new_with_refreshed_list(),
+        unresolved: HashSet::new(),
+    });
+}
+
+/// Cached user and group databases for resolving process owners.
+struct OwnerNames {
+    users: Users,
+    groups: Groups,
+    /// UIDs still unknown after a reload, e.g. from a container or a deleted account.
+    unresolved: HashSet<Uid>,
+}
+
+impl OwnerNames {
+    fn user_name(&self, uid: &Uid) -> Option<String> {
+        self.users.get_user_by_id(uid).map(|u| u.name().to_string())
+    }
+
+    fn group_name(&self, gid: Gid) -> Option<String> {
+        self.groups.list().iter().find(|g| *g.id() == gid).map(|g| g.name().to_string())
+    }
+
+    // Accounts created after startup would otherwise never resolve. Only a UID that hasn't
+    // already failed a reload triggers one, so unresolvable owners don't cost every tick.
+    fn refresh_if_unknown(&mut self, sys: &System) {
+        let new_unknown = sys.processes().values().any(|p| {
+            p.user_id().is_some_and(|uid| {
+                !self.unresolved.contains(uid) && self.users.get_user_by_id(uid).is_none()
+            })
+        });
+        if !new_unknown {
+            return;
+        }
+        self.users.refresh();
+        self.groups.refresh();
+        for uid in sys.processes().values().filter_map(|p| p.user_id()) {
+            if self.users.get_user_by_id(uid).is_none() {
+                self.unresolved.insert(uid.clone());
+            }
+        }
+    }
+}
+
+#[cfg(not(windows))]
+fn uid_number(uid: &Uid) -> Option<u32> {
+    Some(**uid)
+}
+
+#[cfg(windows)]
+fn uid_number(_uid: &Uid) -> Option<u32> {
+    None
+}
+
+// Shared across sampler restarts so a client holding an old sequence number never
+// mistakes a fresh sampler's delta for the next one in its stream.
+static SNAPSHOT_SEQ: AtomicU64 = AtomicU64::new(0);
+static SAMPLE_PSS: AtomicBool = AtomicBool::new(false);
+
+// `refresh_processes` only fills cmd/user for processes that existed when SYS was created,
+// so ask for them explicitly for anything that shows up later.
+fn process_refresh_kind() -> ProcessRefreshKind {
+    ProcessRefreshKind::nothing()
+        .with_memory()
+        .with_cpu()
+        .with_disk_usage()
+        .with_exe(UpdateKind::OnlyIfNotSet)
+        .with_cmd(UpdateKind::OnlyIfNotSet)
+        .with_user(UpdateKind::OnlyIfNotSet)
+        .with_tasks()
+}
+
+// CPU and memory totals are refreshed alongside the processes so `system_overview` lines up
+// with the per-process numbers.
+fn refresh_sys(sys: &mut System) {
+    sys.refresh_cpu_usage();
+    sys.refresh_memory();
+    sys.refresh_processes_specifics(ProcessesToUpdate::All, true, process_refresh_kind());
+    *CHILDREN.lock().unwrap() = Arc::new(build_children_index(sys));
+    OWNERS.lock().unwrap().refresh_if_unknown(sys);
+}
+
+/// Parent -> children map built in one pass over the process table. Thread entries are left
+/// out: they report their process's memory and CPU and would be counted again under it.
+pub(crate) fn build_children_index(sys: &System) -> HashMap<Pid, Vec<Pid>> {
+    let mut index: HashMap<Pid, Vec<Pid>> = HashMap::new();
+    for (pid, process) in sys.processes() {
+        if process.thread_kind() == Some(sysinfo::ThreadKind::Userland) {
+            continue;
+        }
+        if let Some(parent) = process.parent() {
+            index.entry(parent).or_default().push(*pid);
+        }
+    }
+    for children in index.values_mut() {
+        children.sort();
+    }
+    index
+}
+
+/// TIDs that sysinfo lists as entries of their own next to their process on Linux.
+pub(crate) fn thread_ids(sys: &System) -> HashSet<u32> {
+    sys.processes()
+        .values()
+        .filter(|p| p.thread_kind() == Some(sysinfo::ThreadKind::Userland))
+        .map(|p| p.pid().as_u32())
+        .collect()
+}
+
+/// Index matching the last refresh of SYS.
+fn children_index() -> Arc<HashMap<Pid, Vec<Pid>>> {
+    CHILDREN.lock().unwrap().clone()
+}
+
+/// Escapes arbitrary OS string bytes into valid UTF-8: valid sequences pass through,
+/// backslashes are doubled and every byte of an invalid sequence becomes `\xNN`.
+pub(crate) fn escape_os_str(value: &OsStr) -> String {
+    let mut bytes = value.as_encoded_bytes();
+    let mut escaped = String::with_capacity(bytes.len());
+    loop {
+        match std::str::from_utf8(bytes) {
+            Ok(valid) => {
+                escaped.push_str(&valid.replace('\\', "\\\\"));
+                return escaped;
+            }
+            Err(e) => {
+                let (valid, rest) = bytes.split_at(e.valid_up_to());
+                escaped.push_str(&std::str::from_utf8(valid).unwrap().replace('\\', "\\\\"));
+                let invalid_len = e.error_len().unwrap_or(rest.len());
+                for byte in &rest[..invalid_len] {
+                    escaped.push_str(&format!("\\x{:02x}", byte));
+                }
+                bytes = &rest[invalid_len..];
+            }
+        }
+    }
+}
+
+/// Reverses [`escape_os_str`].
+pub(crate) fn unescape_os_string(value: &str) -> Result<OsString, String> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut rest = value.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte != b'\\' {
+            bytes.push(byte);
+            rest = tail;
+            continue;
+        }
+        match tail {
+            [b'\\', tail @ ..] => {
+                bytes.push(b'\\');
+                rest = tail;
+            }
+            [b'x', hi, lo, tail @ ..] => {
+                let byte = std::str::from_utf8(&[*hi, *lo])
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                    .ok_or_else(|| format!("Invalid escape sequence in {:?}", value))?;
+                bytes.push(byte);
+                rest = tail;
+            }
+            _ => return Err(format!("Invalid escape sequence in {:?}", value)),
+        }
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStringExt;
+        Ok(OsString::from_vec(bytes))
+    }
+
+    #[cfg(not(unix))]
+    {
+        Ok(OsString::from(String::from_utf8_lossy(&bytes).into_owned()))
+    }
+}
+
+fn process_info(
+    pid: Pid,
+    process: &sysinfo::Process,
+    children: &HashMap<Pid, Vec<Pid>>,
+    owners: &OwnerNames,
+) -> ProcessInfo {
+    let disk = process.disk_usage();
+    let exe = process.exe().unwrap_or(Path::new(""));
+
+    #[cfg(target_os = "linux")]
+    let (stat, status) = (crate::procfs::read_stat(pid.as_u32()), crate::procfs::read_status(pid.as_u32()));
+
+    #[cfg(unix)]
+    let pgid = match unsafe { libc::getpgid(pid.as_u32() as libc::pid_t) } {
+        -1 => None,
+        pgid => Some(pgid as u32),
+    };
+    #[cfg(not(unix))]
+    let pgid = None;
+
+    #[cfg_attr(not(target_os = "linux"), allow(unused_mut))]
+    let mut info = ProcessInfo {
+        pid: pid.as_u32(),
+        name: process.name().to_string_lossy().into_owned(),
+        name_raw: escape_os_str(process.name()),
+        cmd: process.cmd().iter().map(|a| a.to_string_lossy()).collect::<Vec<_>>().join(" "),
+        cmd_raw: process.cmd().iter().map(|a| escape_os_str(a)).collect(),
+        cpu: process.cpu_usage(),
+        memory: process.memory() as f64 / 1024.0,
+        uptime: process.run_time(),
+        exe: exe.to_string_lossy().into_owned(),
+        exe_raw: escape_os_str(exe.as_os_str()),
+        parent_pid: process.parent().map(|p| p.as_u32()),
+        read_bytes: disk.read_bytes,
+        written_bytes: disk.written_bytes,
+        total_read_bytes: disk.total_read_bytes,
+        total_written_bytes: disk.total_written_bytes,
+        children: children
+            .get(&pid)
+            .map(|c| c.iter().map(|p| p.as_u32()).collect())
+            .unwrap_or_default(),
+        uid: process.user_id().and_then(uid_number),
+        euid: process.effective_user_id().and_then(uid_number),
+        gid: process.group_id().map(|g| *g),
+        egid: process.effective_group_id().map(|g| *g),
+        user: process.user_id().and_then(|uid| owners.user_name(uid)),
+        group: process.group_id().and_then(|gid| owners.group_name(gid)),
+        session_id: process.session_id().map(|s| s.as_u32()),
+        pgid,
+        tty: None,
+        state: process.status().to_string().to_lowercase(),
+        num_threads: process.tasks().map(|t| t.len() as u32),
+        voluntary_ctxt_switches: None,
+        involuntary_ctxt_switches: None,
+        minor_faults: None,
+        major_faults: None,
+        pss: None,
+    };
+
+    #[cfg(target_os = "linux")]
+    {
+        if let Some(stat) = stat {
+            info.tty = crate::procfs::tty_name(stat.tty_nr);
+            info.state = crate::procfs::state_name(stat.state).to_string();
+            info.num_threads = Some(stat.num_threads);
+            info.minor_faults = Some(stat.minflt);
+            info.major_faults = Some(stat.majflt);
+        }
+        if let Some(status) = status {
+            info.voluntary_ctxt_switches = status.get_u64("voluntary_ctxt_switches");
+            info.involuntary_ctxt_switches = status.get_u64("nonvoluntary_ctxt_switches");
+        }
+    }
+
+    info
+}
+
+impl ProcessInfo {
+    fn owned_by(&self, owner: &str) -> bool {
+        self.user.as_deref() == Some(owner)
+            || owner.parse::<u32>().is_ok_and(|uid| self.uid == Some(uid) || self.euid == Some(uid))
+    }
+
+    /// The field named `key` as it would serialize, so rows can be sorted before any of them
+    /// is turned into JSON. List fields give their length, which is what they sort by.
+    fn sort_value(&self, key: &str) -> Option<Value> {
+        Some(match key {
+            "pid" => self.pid.into(),
+            "name" => self.name.as_str().into(),
+            "name_raw" => self.name_raw.as_str().into(),
+            "cmd" => self.cmd.as_str().into(),
+            "cmd_raw" => self.cmd_raw.len().into(),
+            "cpu" => self.cpu.into(),
+            "memory" => self.memory.into(),
+            "uptime" => self.uptime.into(),
+            "parent_pid" => self.parent_pid.into(),
+            "children" => self.children.len().into(),
+            "exe" => self.exe.as_str().into(),
+            "exe_raw" => self.exe_raw.as_str().into(),
+            "read_bytes" => self.read_bytes.into(),
+            "written_bytes" => self.written_bytes.into(),
+            "total_read_bytes" => self.total_read_bytes.into(),
+            "total_written_bytes" => self.total_written_bytes.into(),
+            "uid" => self.uid.into(),
+            "euid" => self.euid.into(),
+            "gid" => self.gid.into(),
+            "egid" => self.egid.into(),
+            "user" => self.user.as_deref().into(),
+            "group" => self.group.as_deref().into(),
+            "session_id" => self.session_id.into(),
+            "pgid" => self.pgid.into(),
+            "tty" => self.tty.as_deref().into(),
+            "state" => self.state.as_str().into(),
+            "num_threads" => self.num_threads.into(),
+            "voluntary_ctxt_switches" => self.voluntary_ctxt_switches.into(),
+            "involuntary_ctxt_switches" => self.involuntary_ctxt_switches.into(),
+            "minor_faults" => self.minor_faults.into(),
+            "major_faults" => self.major_faults.into(),
+            "pss" => self.pss.into(),
+            _ => return None,
+        })
+    }
+}
+
+// Thread entries are skipped: they share their process's address space, and each read walks
+// all of it.
+fn fill_pss(processes: &mut [ProcessInfo]) {
+    #[cfg(target_os = "linux")]
+    {
+        use rayon::prelude::*;
+        let threads = thread_ids(&SYS.lock().unwrap());
+        processes.par_iter_mut().filter(|p| !threads.contains(&p.pid)).for_each(|p| {
+            p.pss = crate::procfs::read_smaps_rollup(p.pid)
+                .and_then(|rollup| rollup.get_u64("Pss"))
+                .map(|kb| kb as f64);
+        });
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = processes;
+    }
+}
+
+fn apply_list_options(mut processes: Vec<ProcessInfo>, options: &ProcessListOptions) -> Vec<ProcessInfo> {
+    if let Some(owner) = &options.owner {
+        processes.retain(|p| p.owned_by(owner));
+    }
+    if options.include_pss && processes.iter().all(|p| p.pss.is_none()) {
+        fill_pss(&mut processes);
+    }
+    if options.sort_by_owner {
+        processes.sort_by(|a, b| (&a.user, a.uid, a.pid).cmp(&(&b.user, b.uid, b.pid)));
+    }
+    processes
+}
+
+/// Checks that the sort key and projected fields of `options` name real process fields.
+fn check_view_fields(options: &ProcessListOptions) -> Result<(), String> {
+    let Ok(Value::Object(known)) = serde_json::to_value(ProcessInfo::default()) else {
+        return Ok(());
+    };
+    let names = options.sort_key.iter().chain(options.fields.iter().flatten());
+    for name in names {
+        if !known.contains_key(name) {
+            return Err(format!("Unknown process field '{}'", name));
+        }
+    }
+    Ok(())
+}
+
+// Nulls (e.g. PSS that could not be read) sort last in either direction.
+fn compare_field(a: Option<&Value>, b: Option<&Value>, direction: SortDirection) -> std::cmp::Ordering {
+    use std::cmp::Ordering::*;
+
+    let ordering = match (a.unwrap_or(&Value::Null), b.unwrap_or(&Value::Null)) {
+        (Value::Null, Value::Null) => return Equal,
+        (Value::Null, _) => return Greater,
+        (_, Value::Null) => return Less,
+        (Value::Number(a), Value::Number(b)) => {
+            a.as_f64().unwrap_or(0.0).total_cmp(&b.as_f64().unwrap_or(0.0))
+        }
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+        (Value::Array(a), Value::Array(b)) => a.len().cmp(&b.len()),
+        _ => Equal,
+    };
+    match direction {
+        SortDirection::Asc => ordering,
+        SortDirection::Desc => ordering.reverse(),
+    }
+}
+
+/// Applies [`apply_list_options`], then sorting, paging and field projection. Only the rows
+/// that are kept get serialized.
+fn select_processes(processes: Vec<ProcessInfo>, options: &ProcessListOptions) -> Result<Vec<Value>, String> {
+    check_view_fields(options)?;
+    let mut processes = apply_list_options(processes, options);
+
+    if let Some(key) = &options.sort_key {
+        let mut keyed: Vec<(Option<Value>, ProcessInfo)> = processes
+            .into_iter()
+            .map(|p| (p.sort_value(key), p))
+            .collect();
+        keyed.sort_by(|a, b| compare_field(a.0.as_ref(), b.0.as_ref(), options.direction));
+        processes = keyed.into_iter().map(|(_, p)| p).collect();
+    }
+
+    let limit = options.limit.unwrap_or(usize::MAX);
+    Ok(processes
+        .iter()
+        .skip(options.offset)
+        .take(limit)
+        .filter_map(|p| match serde_json::to_value(p) {
+            Ok(Value::Object(row)) => Some(row),
+            _ => None,
+        })
+        .map(|row| match &options.fields {
+            Some(fields) => row
+                .into_iter()
+                .filter(|(key, _)| key == "pid" || fields.contains(key))
+                .collect(),
+            None => row,
+        })
+        .map(Value::Object)
+        .collect())
+}
+
+fn collect_processes(sys: &System) -> Vec<ProcessInfo> {
+    let children = children_index();
+    let owners = OWNERS.lock().unwrap();
+    sys.processes()
+        .iter()
+        .map(|(pid, process)| process_info(*pid, process, &children, &owners))
+        .collect()
+}
+
+pub(crate) fn sampler_running() -> bool {
+    SAMPLER.lock().unwrap().control.is_some()
+}
+
+pub(crate) fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Latest process list: the sampler's snapshot while it runs, otherwise a fresh refresh.
+pub(crate) fn current_processes() -> Vec<ProcessInfo> {
+    // While the sampler is running it owns the refresh cadence; refreshing here as well
+    // would shorten its CPU measurement window.
+    if sampler_running() {
+        if let Some(processes) = LAST_PROCESSES.lock().unwrap().as_ref() {
+            return processes.clone();
+        }
+    }
+
+    let mut sys = SYS.lock().unwrap();
+    refresh_sys(&mut sys);
+    collect_processes(&sys)
+}
+
+/// With `fields` set, each row carries only those fields plus `pid`, e.g. the top 50 by
+/// CPU as `{ sort_key: "cpu", direction: "desc", limit: 50, fields: ["name", "cpu"] }`.
+#[tauri::command]
+pub async fn list_processes(options: Option<ProcessListOptions>) -> Result<Vec<Value>, String> {
+    let options = options.unwrap_or_default();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        select_processes(current_processes(), &options)
+    }).await.map_err(|e| e.to_string())?
+}
+
+/// Processes matching a [`Query`] expression such as `cpu > 50 && user == "www"`.
+/// Parse errors carry the character position of the offending token.
+#[tauri::command]
+pub async fn query_processes(query: String, options: Option<ProcessListOptions>) -> Result<Vec<Value>, QueryError> {
+    let query = Query::parse(&query)?;
+    let options = options.unwrap_or_default();
+    if let Ok(Value::Object(known)) = serde_json::to_value(ProcessInfo::default()) {
+        query.check_fields(&known)?;
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut processes = current_processes();
+        if options.include_pss {
+            fill_pss(&mut processes);
+        }
+
+        let matched = processes
+            .into_iter()
+            .filter(|process| match serde_json::to_value(process) {
+                Ok(Value::Object(fields)) => query.matches(&fields),
+                _ => false,
+            })
+            .collect();
+        select_processes(matched, &options).map_err(QueryError::unpositioned)
+    }).await.map_err(|e| QueryError::unpositioned(e.to_string()))?
 }
 
+/// Starts the background sampler if needed and returns a subscription id.
+/// Snapshots are pushed to the frontend as `process_snapshot` events.
 #[tauri::command]
-pub async fn list_processes() -> Vec<ProcessInfo> {
+pub fn subscribe_processes(app_handle: tauri::AppHandle, interval_ms: Option<u64>) -> Result<u64, String> {
+    let mut sampler = SAMPLER.lock().unwrap();
+    let id = sampler.next_subscription;
+    sampler.next_subscription += 1;
+    sampler.subscribers.insert(id);
+
+    if let Some(ms) = interval_ms {
+        sampler.interval_ms = ms.max(MIN_SAMPLE_INTERVAL_MS);
+        if let Some(control) = &sampler.control {
+            let _ = control.send(SamplerMsg::Interval(sampler.interval_ms));
+        }
+    }
+
+    if sampler.control.is_none() {
+        let (tx, rx) = unbounded();
+        let interval = sampler.interval_ms;
+        let view = sampler.view.clone();
+        sampler.control = Some(tx);
+        thread::spawn(move || run_sampler(app_handle, rx, interval, view));
+    }
+
+    Ok(id)
+}
+
+#[tauri::command]
+pub fn unsubscribe_processes(subscription_id: u64) -> Result<String, String> {
+    let mut sampler = SAMPLER.lock().unwrap();
+    if !sampler.subscribers.remove(&subscription_id) {
+        return Err(format!("No subscription with id {}", subscription_id));
+    }
+
+    if sampler.subscribers.is_empty() {
+        if let Some(control) = sampler.control.take() {
+            let _ = control.send(SamplerMsg::Stop);
+        }
+        *LAST_SNAPSHOT.lock().unwrap() = None;
+        *LAST_PROCESSES.lock().unwrap() = None;
+        return Ok("Process sampler stopped".to_string());
+    }
+
+    Ok(format!("Unsubscribed {}", subscription_id))
+}
+
+#[tauri::command]
+pub fn set_sample_interval(interval_ms: u64) -> Result<u64, String> {
+    let mut sampler = SAMPLER.lock().unwrap();
+    sampler.interval_ms = interval_ms.max(MIN_SAMPLE_INTERVAL_MS);
+    if let Some(control) = &sampler.control {
+        let _ = control.send(SamplerMsg::Interval(sampler.interval_ms));
+    }
+    Ok(sampler.interval_ms)
+}
+
+/// Sorts, pages and projects the sampled rows, e.g. a compact "top" view of the 50 busiest
+/// processes. The next tick sends a fresh `process_snapshot` since earlier rows no longer apply.
+#[tauri::command]
+pub fn set_sample_view(options: Option<ProcessListOptions>) -> Result<(), String> {
+    let options = options.unwrap_or_default();
+    check_view_fields(&options)?;
+
+    let mut sampler = SAMPLER.lock().unwrap();
+    sampler.view = options.clone();
+    if let Some(control) = &sampler.control {
+        let _ = control.send(SamplerMsg::View(options));
+    }
+    Ok(())
+}
+
+/// Adds PSS to every sampled process. Off by default because it costs a page table walk
+/// per process on each tick.
+#[tauri::command]
+pub fn set_sample_pss(enabled: bool) -> Result<bool, String> {
+    SAMPLE_PSS.store(enabled, Ordering::Relaxed);
+    Ok(enabled)
+}
+
+/// Each tick refreshes SYS once. While it is still locked, the same process list feeds the
+/// per-process history, the metrics store and the lifecycle tracker. The system-wide
+/// `sample_*` functions (overview, pressure, block devices, network) each advance their own rate
+/// baseline and keep the result. The sampler emits that result as an event, and the matching
+/// command returns it while the sampler runs, so polling never shortens the sampler's
+/// interval. Without the sampler those commands sample on demand, with rates covering the time
+/// since the previous call.
+fn run_sampler(
+    app_handle: tauri::AppHandle,
+    rx: Receiver<SamplerMsg>,
+    mut interval_ms: u64,
+    mut view: ProcessListOptions,
+) {
+    // CPU usage is a delta between two refreshes, so prime SYS once and wait the minimum
+    // interval; otherwise the first snapshot reports 0% (or an average since startup).
+    refresh_sys(&mut SYS.lock().unwrap());
+    thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    crate::lifecycle::reset();
+
+    let mut previous: Option<(u64, HashMap<u32, Value>)> = None;
+    loop {
+        let tick = Instant::now();
+        let seq = SNAPSHOT_SEQ.fetch_add(1, Ordering::SeqCst) + 1;
+
+        let timestamp_ms = now_ms();
+        let (mut processes, overview, stored, lifecycle) = {
+            let mut sys = SYS.lock().unwrap();
+            refresh_sys(&mut sys);
+            let processes = collect_processes(&sys);
+            crate::history::record(&sys, &processes, timestamp_ms, interval_ms);
+            let stored = crate::metrics_store::sample_from(&sys, &processes, timestamp_ms, interval_ms);
+            let lifecycle = crate::lifecycle::observe(&sys, &processes, timestamp_ms);
+            (processes, crate::system_overview::sample_overview(&sys), stored, lifecycle)
+        };
+        if let Err(e) = crate::lifecycle::publish(&app_handle, &lifecycle) {
+            println!("Failed to record lifecycle events: {}", e);
+        }
+        if let Some(Err(e)) = stored.map(|sample| crate::metrics_store::record(&app_handle, &sample)) {
+            println!("Failed to store metrics: {}", e);
+        }
+        if let Err(e) = app_handle.emit("system_overview", &overview) {
+            println!("Failed to emit system overview: {}", e);
+        }
+        #[cfg(target_os = "linux")]
+        if let Err(e) = app_handle.emit("system_pressure", &crate::pressure::sample_pressure()) {
+            println!("Failed to emit system pressure: {}", e);
+        }
+        if let Err(e) = app_handle.emit("block_devices", &crate::disks::sample_block_devices()) {
+            println!("Failed to emit block device stats: {}", e);
+        }
+        if let Err(e) = app_handle.emit("network_stats", &crate::network::sample_network()) {
+            println!("Failed to emit network stats: {}", e);
+        }
+        if SAMPLE_PSS.load(Ordering::Relaxed) {
+            fill_pss(&mut processes);
+        }
+        let rows = select_processes(processes.clone(), &view).unwrap_or_else(|e| {
+            println!("Invalid sample view: {}", e);
+            vec![]
+        });
+        let current: HashMap<u32, Value> = rows
+            .iter()
+            .filter_map(|row| Some((row.get("pid")?.as_u64()? as u32, row.clone())))
+            .collect();
+
+        // LAST_SNAPSHOT is updated before emitting so a client resyncing on this event gets `seq`.
+        let snapshot = ProcessSnapshot { seq, timestamp_ms, interval_ms, processes: rows };
+        *LAST_PROCESSES.lock().unwrap() = Some(processes);
+        match &previous {
+            Some((base_seq, before)) => {
+                let delta = diff_processes(*base_seq, seq, timestamp_ms, interval_ms, before, &current, &snapshot.processes);
+                *LAST_SNAPSHOT.lock().unwrap() = Some(snapshot);
+                if let Err(e) = app_handle.emit("process_delta", &delta) {
+                    println!("Failed to emit process delta: {}", e);
+                }
+            }
+            None => {
+                *LAST_SNAPSHOT.lock().unwrap() = Some(snapshot.clone());
+                if let Err(e) = app_handle.emit("process_snapshot", &snapshot) {
+                    println!("Failed to emit process snapshot: {}", e);
+                }
+            }
+        }
+        previous = Some((seq, current));
+
+        loop {
+            let wait = Duration::from_millis(interval_ms).saturating_sub(tick.elapsed());
+            match rx.recv_timeout(wait) {
+                Ok(SamplerMsg::Interval(ms)) => interval_ms = ms,
+                Ok(SamplerMsg::View(options)) => {
+                    view = options;
+                    previous = None;
+                }
+                Ok(SamplerMsg::Stop) | Err(RecvTimeoutError::Disconnected) => return,
+                Err(RecvTimeoutError::Timeout) => break,
+            }
+        }
+    }
+}
+
+fn diff_processes(
+    base_seq: u64,
+    seq: u64,
+    timestamp_ms: u64,
+    interval_ms: u64,
+    before: &HashMap<u32, Value>,
+    after: &HashMap<u32, Value>,
+    rows: &[Value],
+) -> ProcessDelta {
+    let mut added = vec![];
+    let mut changed = vec![];
+
+    for row in rows {
+        let Some(pid) = row.get("pid").and_then(Value::as_u64).map(|pid| pid as u32) else {
+            continue;
+        };
+        let Some(old) = before.get(&pid) else {
+            added.push(row.clone());
+            continue;
+        };
+        let (Some(old), Some(new)) = (old.as_object(), row.as_object()) else {
+            continue;
+        };
+
+        let mut fields: Map<String, Value> = new
+            .iter()
+            .filter(|(key, value)| old.get(key.as_str()) != Some(value))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        if !fields.is_empty() {
+            fields.insert("pid".to_string(), Value::from(pid));
+            changed.push(fields);
+        }
+    }
+
+    let removed = before.keys().filter(|pid| !after.contains_key(pid)).copied().collect();
+
+    ProcessDelta { seq, base_seq, timestamp_ms, interval_ms, added, removed, changed }
+}
+
+/// Full copy of the latest sampled snapshot, for clients that missed a `process_delta`.
+#[tauri::command]
+pub fn resync_processes() -> Result<ProcessSnapshot, String> {
+    LAST_SNAPSHOT
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| "Process sampler has not produced a snapshot yet".to_string())
+}
+
+#[derive(Serialize, Clone)]
+pub struct ProcessTreeNode {
+    pid: u32,
+    name: String,
+    cpu: f32,
+    memory: f64,
+    read_bytes: u64,
+    written_bytes: u64,
+    subtree_processes: usize,
+    subtree_cpu: f32,
+    subtree_memory: f64,
+    subtree_read_bytes: u64,
+    subtree_written_bytes: u64,
+    children: Vec<ProcessTreeNode>,
+}
+
+/// Nested process tree rooted at `pid`, or one tree per root process when `pid` is `None`.
+/// Every node carries totals for itself plus all of its descendants.
+#[tauri::command]
+pub async fn process_tree(pid: Option<u32>) -> Result<Vec<ProcessTreeNode>, String> {
+    let sampling = sampler_running();
     let sys_clone = SYS.clone();
 
     tauri::async_runtime::spawn_blocking(move || {
         let mut sys = sys_clone.lock().unwrap();
-        sys.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
-
-        // sleep NOT needed anymore because we keep state
-        // just call refresh multiple times
-
-        let mut processes = vec![];
-
-        for (pid, process) in sys.processes() {
-            let disk = process.disk_usage();
-            let read_bytes = disk.read_bytes;
-            let written_bytes = disk.written_bytes;
-            let total_read_bytes = disk.total_read_bytes;
-            let total_written_bytes = disk.total_written_bytes;
-            processes.push(ProcessInfo {
-                pid: pid.as_u32(),
-                               name: process.name().to_str().unwrap().to_string(),
-                               cmd: process.cmd().join(" ".as_ref()).into_string().unwrap(),
-                               cpu: process.cpu_usage(),
-                               memory: process.memory() as f64 / 1024.0,
-                               uptime: process.run_time(),
-                               exe: process.exe().unwrap_or(Path::new("")).to_str().unwrap().to_string(),
-                               parent_pid: process.parent().map(|p| p.as_u32()),
-                               read_bytes,
-                               written_bytes,
-                               total_read_bytes,
-                               total_written_bytes,
-                               children: sys.processes()
-                                                   .iter()
-                                                   .filter_map(|(cpid, cp)| if cp.parent() == Some(*pid) {
-                                                       Some(cpid.as_u32())
-                                                   } else { None })
-                                                   .collect(),
-            });
+        if !sampling {
+            refresh_sys(&mut sys);
+        }
+        let children = children_index();
+        let mut visited = HashSet::new();
+
+        match pid {
+            Some(pid) => {
+                let root = Pid::from_u32(pid);
+                build_tree_node(root, &sys, &children, &mut visited)
+                    .map(|node| vec![node])
+                    .ok_or_else(|| format!("No process with PID {}", pid))
+            }
+            None => {
+                let mut roots: Vec<Pid> = sys.processes()
+                    .iter()
+                    .filter(|(_, p)| p.parent().is_none_or(|parent| sys.process(parent).is_none()))
+                    .map(|(pid, _)| *pid)
+                    .collect();
+                roots.sort();
+                Ok(roots
+                    .into_iter()
+                    .filter_map(|root| build_tree_node(root, &sys, &children, &mut visited))
+                    .collect())
+            }
         }
+    }).await.map_err(|e| e.to_string())?
+}
+
+fn build_tree_node(
+    pid: Pid,
+    sys: &System,
+    children: &HashMap<Pid, Vec<Pid>>,
+    visited: &mut HashSet<Pid>,
+) -> Option<ProcessTreeNode> {
+    // Guards against PID reuse producing a parent cycle between two refreshes.
+    if !visited.insert(pid) {
+        return None;
+    }
+    let process = sys.process(pid)?;
+    let disk = process.disk_usage();
+
+    let mut node = ProcessTreeNode {
+        pid: pid.as_u32(),
+        name: process.name().to_string_lossy().into_owned(),
+        cpu: process.cpu_usage(),
+        memory: process.memory() as f64 / 1024.0,
+        read_bytes: disk.read_bytes,
+        written_bytes: disk.written_bytes,
+        subtree_processes: 1,
+        subtree_cpu: process.cpu_usage(),
+        subtree_memory: process.memory() as f64 / 1024.0,
+        subtree_read_bytes: disk.read_bytes,
+        subtree_written_bytes: disk.written_bytes,
+        children: vec![],
+    };
 
-        processes
-    }).await.expect("thread panicked")
+    for child in children.get(&pid).map(Vec::as_slice).unwrap_or(&[]) {
+        if let Some(child_node) = build_tree_node(*child, sys, children, visited) {
+            node.subtree_processes += child_node.subtree_processes;
+            node.subtree_cpu += child_node.subtree_cpu;
+            node.subtree_memory += child_node.subtree_memory;
+            node.subtree_read_bytes += child_node.subtree_read_bytes;
+            node.subtree_written_bytes += child_node.subtree_written_bytes;
+            node.children.push(child_node);
+        }
+    }
+
+    Some(node)
 }
 
 use tauri::{command, AppHandle, Listener, Manager, State};
@@ -128,18 +985,199 @@ pub fn set_process_priority(pid: u32, priority: i32,app_handle: tauri::AppHandle
         }
     }
 }
-#[command]
-pub fn restart_process(app_handle: tauri::AppHandle,exe_path: String, args: Vec<String>) -> Result<String, String> {
-    use std::process::Command;
+/// How a process was launched, in the escaped form of [`escape_os_str`] so that non-UTF-8
+/// arguments and environment values are relaunched byte for byte.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LaunchSpec {
+    exe: String,
+    argv0: Option<String>,
+    args: Vec<String>,
+    cwd: Option<String>,
+    // `None` when the environment could not be read; the child then inherits ours.
+    env: Option<Vec<(String, String)>>,
+}
+
+/// Optional replacements for each part of a captured [`LaunchSpec`]. `env` entries are set
+/// on top of the captured environment rather than replacing it.
+#[derive(Deserialize, Default, Debug)]
+#[serde(default)]
+pub struct LaunchOverrides {
+    exe_path: Option<String>,
+    args: Option<Vec<String>>,
+    cwd: Option<String>,
+    env: Option<HashMap<String, String>>,
+}
 
-    let result = Command::new(&exe_path)
-        .args(&args)
-        .spawn();
+impl LaunchSpec {
+    fn from_exe(exe_path: String, args: Vec<String>) -> Self {
+        LaunchSpec { exe: exe_path, argv0: None, args, cwd: None, env: None }
+    }
 
-    match result {
+    fn with_overrides(mut self, overrides: LaunchOverrides) -> Self {
+        if let Some(exe_path) = overrides.exe_path {
+            if exe_path != self.exe {
+                self.argv0 = None;
+            }
+            self.exe = exe_path;
+        }
+        if let Some(args) = overrides.args {
+            self.args = args;
+        }
+        if let Some(cwd) = overrides.cwd {
+            self.cwd = Some(cwd);
+        }
+        if let Some(overrides) = overrides.env {
+            let env = self.env.get_or_insert_with(|| {
+                std::env::vars_os()
+                    .map(|(k, v)| (escape_os_str(&k), escape_os_str(&v)))
+                    .collect()
+            });
+            for (key, value) in overrides {
+                match env.iter_mut().find(|(k, _)| *k == key) {
+                    Some(entry) => entry.1 = value,
+                    None => env.push((key, value)),
+                }
+            }
+        }
+        self
+    }
+
+    fn command(&self) -> Result<Command, String> {
+        let mut command = Command::new(unescape_os_string(&self.exe)?);
+        #[cfg(unix)]
+        if let Some(argv0) = &self.argv0 {
+            use std::os::unix::process::CommandExt;
+            command.arg0(unescape_os_string(argv0)?);
+        }
+        for arg in &self.args {
+            command.arg(unescape_os_string(arg)?);
+        }
+        if let Some(cwd) = &self.cwd {
+            command.current_dir(unescape_os_string(cwd)?);
+        }
+        if let Some(env) = &self.env {
+            command.env_clear();
+            for (key, value) in env {
+                command.env(unescape_os_string(key)?, unescape_os_string(value)?);
+            }
+        }
+        Ok(command)
+    }
+
+    fn spawn(&self) -> Result<Child, String> {
+        self.command()?.spawn().map_err(|e| e.to_string())
+    }
+}
+
+/// Reads the full argv, working directory and environment of a running process.
+pub(crate) fn capture_launch_spec(pid: u32) -> Result<LaunchSpec, String> {
+    #[cfg(target_os = "linux")]
+    {
+        capture_launch_spec_linux(pid)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let mut sys = System::new();
+        sys.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[Pid::from_u32(pid)]),
+            true,
+            ProcessRefreshKind::everything(),
+        );
+        let process = sys.process(Pid::from_u32(pid)).ok_or_else(|| format!("No process with PID {}", pid))?;
+        let cmd = process.cmd();
+        let exe = match process.exe() {
+            Some(exe) => escape_os_str(exe.as_os_str()),
+            None => cmd.first().map(|a| escape_os_str(a)).ok_or_else(|| format!("PID {} has no command line", pid))?,
+        };
+        let env = process.environ()
+            .iter()
+            .filter_map(|entry| {
+                // '=' is never part of an escape sequence, so splitting the escaped form is safe.
+                let entry = escape_os_str(entry);
+                let (key, value) = entry.split_once('=')?;
+                Some((key.to_string(), value.to_string()))
+            })
+            .collect::<Vec<_>>();
+
+        Ok(LaunchSpec {
+            exe,
+            argv0: cmd.first().map(|a| escape_os_str(a)),
+            args: cmd.iter().skip(1).map(|a| escape_os_str(a)).collect(),
+            cwd: process.cwd().map(|p| escape_os_str(p.as_os_str())),
+            env: if env.is_empty() { None } else { Some(env) },
+        })
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn capture_launch_spec_linux(pid: u32) -> Result<LaunchSpec, String> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let proc_dir = Path::new("/proc").join(pid.to_string());
+    // Every entry is NUL-terminated; only the final terminator is dropped so empty arguments survive.
+    let split_nul = |bytes: &[u8]| -> Vec<Vec<u8>> {
+        let bytes = bytes.strip_suffix(&[0]).unwrap_or(bytes);
+        if bytes.is_empty() {
+            return vec![];
+        }
+        bytes.split(|b| *b == 0).map(<[u8]>::to_vec).collect()
+    };
+
+    let cmdline = std::fs::read(proc_dir.join("cmdline"))
+        .map_err(|e| format!("Failed to read command line of PID {}: {}", pid, e))?;
+    let argv = split_nul(&cmdline);
+    let Some(argv0) = argv.first() else {
+        return Err(format!("PID {} has no command line (kernel thread or zombie)", pid));
+    };
+
+    // A binary replaced on disk shows up as "<path> (deleted)"; relaunch whatever is at the path now.
+    let exe = match std::fs::read_link(proc_dir.join("exe")) {
+        Ok(path) => {
+            let bytes = path.as_os_str().as_bytes();
+            let bytes = bytes.strip_suffix(b" (deleted)").unwrap_or(bytes);
+            escape_os_str(OsStr::from_bytes(bytes))
+        }
+        Err(_) => escape_os_str(OsStr::from_bytes(argv0)),
+    };
+
+    let cwd = std::fs::read_link(proc_dir.join("cwd"))
+        .ok()
+        .map(|p| escape_os_str(p.as_os_str()));
+
+    let env = std::fs::read(proc_dir.join("environ")).ok().map(|environ| {
+        split_nul(&environ)
+            .iter()
+            .filter(|entry| !entry.is_empty())
+            .filter_map(|entry| {
+                let eq = entry.iter().position(|b| *b == b'=')?;
+                Some((
+                    escape_os_str(OsStr::from_bytes(&entry[..eq])),
+                    escape_os_str(OsStr::from_bytes(&entry[eq + 1..])),
+                ))
+            })
+            .collect()
+    });
+
+    Ok(LaunchSpec {
+        exe,
+        argv0: Some(escape_os_str(OsStr::from_bytes(argv0))),
+        args: argv[1..].iter().map(|a| escape_os_str(OsStr::from_bytes(a))).collect(),
+        cwd,
+        env,
+    })
+}
+
+#[command]
+pub fn launch_spec(pid: u32) -> Result<LaunchSpec, String> {
+    capture_launch_spec(pid)
+}
+
+fn spawn_and_report(app_handle: tauri::AppHandle, spec: &LaunchSpec) -> Result<String, String> {
+    match spec.spawn() {
         Ok(child) => {
-            emit_log(app_handle,format!("🚀 Restarted process {} with PID {}", exe_path, child.id()));
-            Ok(format!("🚀 Restarted process {} with PID {}", exe_path, child.id()))
+            emit_log(app_handle,format!("🚀 Restarted process {} with PID {}", spec.exe, child.id()));
+            Ok(format!("🚀 Restarted process {} with PID {}", spec.exe, child.id()))
         },
         Err(e) => {
             emit_log(app_handle,format!("❌ Failed to restart process: {}", e));
@@ -147,16 +1185,43 @@ pub fn restart_process(app_handle: tauri::AppHandle,exe_path: String, args: Vec<
         },
     }
 }
+
+#[command]
+pub fn restart_process(
+    app_handle: tauri::AppHandle,
+    exe_path: String,
+    args: Vec<String>,
+    cwd: Option<String>,
+    env: Option<HashMap<String, String>>,
+) -> Result<String, String> {
+    let spec = LaunchSpec::from_exe(exe_path, args)
+        .with_overrides(LaunchOverrides { cwd, env, ..Default::default() });
+    spawn_and_report(app_handle, &spec)
+}
+
+/// Kills `pid` (and optionally its descendants) and relaunches it with the argv, cwd and
+/// environment captured beforehand. Each part set in `overrides` replaces the captured one.
 #[command]
 pub fn kill_and_restart(
     pid: u32,
     kill_children: bool,
     timeout_secs: u64,
-    exe_path: String,
-    args: Vec<String>,
+    overrides: Option<LaunchOverrides>,
     app_handle: tauri::AppHandle
 ) -> Result<String, String> {
     use rayon::prelude::*;
+    let overrides = overrides.unwrap_or_default();
+    let spec = match capture_launch_spec(pid) {
+        Ok(spec) => spec.with_overrides(overrides),
+        Err(e) => match overrides.exe_path.clone() {
+            Some(exe_path) => {
+                emit_log(app_handle.clone(), format!("⚠️ Could not capture launch details of PID {}: {}", pid, e));
+                LaunchSpec::from_exe(exe_path, vec![]).with_overrides(overrides)
+            }
+            None => return Err(format!("❌ Could not capture launch details of PID {}: {}", pid, e)),
+        },
+    };
+
     std::thread::spawn(move || {
         let mut sys = System::new_all();
         sys.refresh_all();
@@ -165,7 +1230,8 @@ pub fn kill_and_restart(
         let mut descendants = vec![];
 
         if kill_children {
-            crate::kill_process::collect_descendants_with_depth(target_pid, &sys, &mut descendants, 1,app_handle.clone());
+            let children = build_children_index(&sys);
+            crate::kill_process::collect_descendants_with_depth(target_pid, &children, &mut descendants, 1,app_handle.clone());
             descendants.sort_by(|a, b| b.1.cmp(&a.1)); // Deepest first
         }
 
@@ -186,13 +1252,9 @@ pub fn kill_and_restart(
         emit_log(app_handle.clone(),format!("📝 Kill results:\n{}", summary));
         println!("📝 Kill results:\n{}", summary);
 
-        // You could emit to frontend here with: app.emit_all("kill_summary", summary).unwrap();
-        let restart_report = restart_process(app_handle.clone(),exe_path, args);
-        if restart_report.clone().is_ok() {
-            emit_log(app_handle.clone(),format!("{}", restart_report.clone().unwrap()));
-            println!("{}", restart_report.clone().unwrap())
-        } else {
-            emit_log(app_handle.clone(),format!("{}", "Restart failde",));
+        match spawn_and_report(app_handle.clone(), &spec) {
+            Ok(report) => println!("{}", report),
+            Err(report) => println!("{}", report),
         }
     });
     Ok("".to_string())
@@ -200,7 +1262,7 @@ pub fn kill_and_restart(
 
 use tauri::Window;
 use tauri::Emitter;
-use crossbeam_channel::{unbounded, Sender, Receiver};
+use crossbeam_channel::{unbounded, Sender, Receiver, RecvTimeoutError};
 use rayon::iter::IntoParallelRefIterator;
 
 lazy_static! {
@@ -210,13 +1272,24 @@ lazy_static! {
 #[command]
 pub fn auto_respawn(
     app_handle: tauri::AppHandle,
-    exe_path: String,
-    args: Vec<String>,
+    exe_path: Option<String>,
+    args: Option<Vec<String>>,
     pid: u32,
     check_interval: u64,
     restart_delay: u64,
     max_restarts: u32,
 ) -> Result<String, String> {
+    let overrides = LaunchOverrides { exe_path, args, ..Default::default() };
+    let spec = match capture_launch_spec(pid) {
+        Ok(spec) => spec.with_overrides(overrides),
+        Err(e) => match overrides.exe_path.clone() {
+            Some(exe_path) => {
+                emit_log(app_handle.clone(), format!("⚠️ Could not capture launch details of PID {}: {}", pid, e));
+                LaunchSpec::from_exe(exe_path, vec![]).with_overrides(overrides)
+            }
+            None => return Err(format!("❌ Could not capture launch details of PID {}: {}", pid, e)),
+        },
+    };
     let (tx, rx) = unbounded();
 
     // Clone once for the main thread
@@ -235,10 +1308,7 @@ pub fn auto_respawn(
             }
 
             println!("🚀 Starting process...");
-            let mut child = Command::new(&exe_path)
-                .args(&args)
-                .spawn()
-                .ok();
+            let mut child = spec.spawn().ok();
             let app_handle_inner = app_handle_thread.clone();
             loop {
                 thread::sleep(Duration::from_secs(check_interval));
@@ -295,33 +1365,27 @@ pub fn batch_set_priority(app_handle: tauri::AppHandle,pids: Vec<u32>, priority:
     Ok(results.join("\n"))
 }
 
-#[command]
-pub fn pid_to_proc(pid:u32,app_handle: tauri::AppHandle) -> Result<ProcessInfo,String> {
+/// Runs `f` against SYS, refreshing it first unless the sampler owns the refresh cadence.
+pub(crate) fn with_refreshed_sys<R>(f: impl FnOnce(&System) -> R) -> R {
+    let sampling = sampler_running();
     let mut sys = SYS.lock().unwrap();
-    sys.refresh_all();
-    let proc = sys.process(Pid::from_u32(pid));
-    if let Some(p) = proc {
-        Ok(ProcessInfo {
-            pid:pid,
-            name: p.name().to_str().unwrap().to_string(),
-            cmd: p.cmd().join(" ".as_ref()).into_string().unwrap(),
-            cpu: p.cpu_usage(),
-            memory: p.memory() as f64 / 1024.0,
-            uptime: p.run_time(),
-            exe: p.exe().unwrap_or(Path::new("")).to_str().unwrap().to_string(),
-            read_bytes:p.disk_usage().read_bytes,
-            written_bytes:p.disk_usage().written_bytes,
-            total_read_bytes:p.disk_usage().total_read_bytes,
-            total_written_bytes:p.disk_usage().total_written_bytes,
-            parent_pid: p.parent().map(|p| p.as_u32()),
-            children: sys.processes()
-                .iter()
-                .filter_map(|(cpid, cp)| if cp.parent() == Some(Pid::from_u32(pid)) {
-                    Some(cpid.as_u32())
-                } else { None })
-                .collect(),
-        })
+    if !sampling {
+        refresh_sys(&mut sys);
+    }
+    f(&sys)
+}
+
+pub(crate) fn process_by_pid(pid: u32) -> Option<ProcessInfo> {
+    with_refreshed_sys(|sys| {
+        let proc = sys.process(Pid::from_u32(pid))?;
+        Some(process_info(Pid::from_u32(pid), proc, &children_index(), &OWNERS.lock().unwrap()))
+    })
+}
 
+#[command]
+pub fn pid_to_proc(pid:u32,app_handle: tauri::AppHandle) -> Result<ProcessInfo,String> {
+    if let Some(p) = process_by_pid(pid) {
+        Ok(p)
     } else {
         emit_log(app_handle, String::from("No such process"));
         Err("No such process".to_string())
@@ -334,4 +1398,95 @@ pub fn emit_log(app_handle: tauri::AppHandle, message: String) -> Result<(), Str
         .map_err(|e| format!("Failed to emit event: {}", e))?;
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    fn os(bytes: &[u8]) -> OsString {
+        use std::os::unix::ffi::OsStringExt;
+        OsString::from_vec(bytes.to_vec())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn escaped_os_strings_round_trip() {
+        let cases: [&[u8]; 7] = [
+            b"",
+            b"plain ascii",
+            "h\u{e9}llo w\u{f6}rld".as_bytes(),
+            b"C:\\path\\x41",
+            b"\xff\xfe bad \x80 bytes",
+            b"truncated \xe2\x82",
+            b"\\\xff\\x",
+        ];
+        for bytes in cases {
+            let escaped = escape_os_str(&os(bytes));
+            assert_eq!(unescape_os_string(&escaped).unwrap(), os(bytes), "{:?}", escaped);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn escape_format() {
+        assert_eq!(escape_os_str(&os(b"a\\b")), "a\\\\b");
+        assert_eq!(escape_os_str(&os(b"a\xffb")), "a\\xffb");
+        assert_eq!(escape_os_str(&os("\u{e9}".as_bytes())), "\u{e9}");
+        assert_eq!(escape_os_str(&os(b"\xe2\x82")), "\\xe2\\x82");
+    }
+
+    #[test]
+    fn invalid_escapes_are_rejected() {
+        for value in ["\\", "\\q", "\\x4", "\\xzz", "trailing \\x"] {
+            assert!(unescape_os_string(value).is_err(), "{:?}", value);
+        }
+    }
+
+    #[test]
+    fn every_field_has_a_sort_value() {
+        let process = ProcessInfo::default();
+        let Ok(Value::Object(row)) = serde_json::to_value(&process) else {
+            panic!("ProcessInfo should serialize to an object");
+        };
+        for key in row.keys() {
+            assert!(process.sort_value(key).is_some(), "{}", key);
+        }
+    }
+
+    #[test]
+    fn sorting_paging_and_projection() {
+        let process = |pid: u32, cpu: f32, pss: Option<f64>| ProcessInfo { pid, cpu, pss, ..Default::default() };
+        let processes = vec![process(1, 5.0, Some(10.0)), process(2, 50.0, None), process(3, 20.0, Some(30.0))];
+        let pids = |rows: Vec<Value>| rows.iter().map(|row| row["pid"].as_u64().unwrap()).collect::<Vec<_>>();
+
+        let options = ProcessListOptions {
+            sort_key: Some("cpu".to_string()),
+            direction: SortDirection::Desc,
+            limit: Some(2),
+            fields: Some(vec!["cpu".to_string()]),
+            ..Default::default()
+        };
+        let rows = select_processes(processes.clone(), &options).unwrap();
+        assert_eq!(pids(rows.clone()), vec![2, 3]);
+        assert_eq!(rows[0].as_object().unwrap().len(), 2);
+
+        // Nulls sort last in either direction.
+        for direction in [SortDirection::Asc, SortDirection::Desc] {
+            let options = ProcessListOptions { sort_key: Some("pss".to_string()), direction, ..Default::default() };
+            assert_eq!(pids(select_processes(processes.clone(), &options).unwrap())[2], 2);
+        }
+
+        let options = ProcessListOptions { sort_key: Some("pid".to_string()), offset: 1, ..Default::default() };
+        assert_eq!(pids(select_processes(processes.clone(), &options).unwrap()), vec![2, 3]);
+    }
+
+    #[test]
+    fn unknown_view_fields_are_rejected_even_without_rows() {
+        let options = ProcessListOptions { fields: Some(vec!["cpuu".to_string()]), ..Default::default() };
+        assert_eq!(select_processes(vec![], &options).unwrap_err(), "Unknown process field 'cpuu'");
+        let options = ProcessListOptions { sort_key: Some("nmae".to_string()), ..Default::default() };
+        assert!(select_processes(vec![], &options).is_err());
+    }
+}
@@ -0,0 +1,102 @@
+#[cfg(target_os = "linux")]
+use std::collections::HashMap;
+#[cfg(target_os = "linux")]
+use std::sync::Mutex;
+#[cfg(target_os = "linux")]
+use std::time::Instant;
+#[cfg(target_os = "linux")]
+use lazy_static::lazy_static;
+use serde::Serialize;
+
+#[derive(Serialize, Clone)]
+pub struct ThreadInfo {
+    tid: u32,
+    name: String,
+    state: String,
+    /// `None` for a thread first seen in this call, which has no earlier sample to compare to.
+    cpu: Option<f32>,
+    nice: i32,
+    last_cpu: Option<u32>,
+}
+
+// tid -> (utime + stime ticks, when they were read)
+#[cfg(target_os = "linux")]
+type ThreadTicks = HashMap<u32, (u64, Instant)>;
+
+#[cfg(target_os = "linux")]
+lazy_static! {
+    // Keyed by pid.
+    static ref THREAD_TICKS: Mutex<HashMap<u32, ThreadTicks>> = Mutex::new(HashMap::new());
+}
+
+/// Lists the threads of `pid`. CPU usage is measured against the previous call for the same
+/// PID; the first call takes its own short baseline so it never reports a bogus 0%.
+#[tauri::command]
+pub async fn list_threads(pid: u32) -> Result<Vec<ThreadInfo>, String> {
+    #[cfg(target_os = "linux")]
+    {
+        tauri::async_runtime::spawn_blocking(move || list_threads_linux(pid))
+            .await
+            .map_err(|e| e.to_string())?
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = pid;
+        Err("Unsupported platform".to_string())
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn list_threads_linux(pid: u32) -> Result<Vec<ThreadInfo>, String> {
+    use crate::procfs::{clock_ticks_per_sec, read_task_stat, state_name, ProcStat};
+
+    let read_threads = || -> Result<Vec<(u32, ProcStat, Instant)>, String> {
+        let entries = std::fs::read_dir(format!("/proc/{}/task", pid))
+            .map_err(|e| format!("Failed to read threads of PID {}: {}", pid, e))?;
+        Ok(entries
+            .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse::<u32>().ok())
+            .filter_map(|tid| Some((tid, read_task_stat(pid, tid)?, Instant::now())))
+            .collect())
+    };
+
+    let has_baseline = THREAD_TICKS.lock().unwrap().contains_key(&pid);
+    if !has_baseline {
+        let baseline = read_threads()?
+            .into_iter()
+            .map(|(tid, stat, at)| (tid, (stat.utime + stat.stime, at)))
+            .collect();
+        THREAD_TICKS.lock().unwrap().insert(pid, baseline);
+        std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    }
+
+    let threads = read_threads()?;
+    let ticks_per_sec = clock_ticks_per_sec();
+    let mut ticks = THREAD_TICKS.lock().unwrap();
+    // Drop PIDs that have exited so the map doesn't grow forever.
+    ticks.retain(|p, _| *p == pid || std::path::Path::new(&format!("/proc/{}", p)).exists());
+    let previous = ticks.remove(&pid).unwrap_or_default();
+
+    let mut current = HashMap::new();
+    let mut result = Vec::with_capacity(threads.len());
+    for (tid, stat, at) in threads {
+        let total = stat.utime + stat.stime;
+        let cpu = previous.get(&tid).and_then(|(prev_total, prev_at)| {
+            let elapsed = at.duration_since(*prev_at).as_secs_f64();
+            (elapsed > 0.0).then(|| (total.saturating_sub(*prev_total) as f64 / ticks_per_sec / elapsed * 100.0) as f32)
+        });
+        current.insert(tid, (total, at));
+        result.push(ThreadInfo {
+            tid,
+            name: stat.comm,
+            state: state_name(stat.state).to_string(),
+            cpu,
+            nice: stat.nice,
+            last_cpu: stat.processor,
+        });
+    }
+    ticks.insert(pid, current);
+
+    result.sort_by_key(|t| t.tid);
+    Ok(result)
+}
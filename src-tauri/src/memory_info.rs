@@ -0,0 +1,80 @@
+use serde::Serialize;
+
+/// Memory breakdown of one process. All values are KiB, like `ProcessInfo.memory`.
+#[derive(Serialize, Clone, Default)]
+pub struct MemoryDetails {
+    pid: u32,
+    rss: u64,
+    virtual_size: u64,
+    pss: Option<u64>,
+    pss_anon: Option<u64>,
+    pss_file: Option<u64>,
+    pss_shmem: Option<u64>,
+    shared: Option<u64>,
+    shared_clean: Option<u64>,
+    shared_dirty: Option<u64>,
+    private_clean: Option<u64>,
+    private_dirty: Option<u64>,
+    anonymous: Option<u64>,
+    file_backed: Option<u64>,
+    shmem: Option<u64>,
+    swap: Option<u64>,
+    swap_pss: Option<u64>,
+    locked: Option<u64>,
+    peak_rss: Option<u64>,
+}
+
+/// Reads `/proc/<pid>/status` and `smaps_rollup`. The rollup needs ptrace access to the
+/// target, so for other users' processes only the `status` figures are filled in.
+#[tauri::command]
+pub async fn memory_details(pid: u32) -> Result<MemoryDetails, String> {
+    #[cfg(target_os = "linux")]
+    {
+        tauri::async_runtime::spawn_blocking(move || memory_details_linux(pid))
+            .await
+            .map_err(|e| e.to_string())?
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = pid;
+        Err("Unsupported platform".to_string())
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn memory_details_linux(pid: u32) -> Result<MemoryDetails, String> {
+    use crate::procfs::{read_smaps_rollup, read_status};
+
+    let status = read_status(pid).ok_or_else(|| format!("No process with PID {}", pid))?;
+    let mut details = MemoryDetails {
+        pid,
+        rss: status.get_u64("VmRSS").unwrap_or(0),
+        virtual_size: status.get_u64("VmSize").unwrap_or(0),
+        anonymous: status.get_u64("RssAnon"),
+        file_backed: status.get_u64("RssFile"),
+        shmem: status.get_u64("RssShmem"),
+        swap: status.get_u64("VmSwap"),
+        locked: status.get_u64("VmLck"),
+        peak_rss: status.get_u64("VmHWM"),
+        ..Default::default()
+    };
+
+    if let Some(rollup) = read_smaps_rollup(pid) {
+        details.pss = rollup.get_u64("Pss");
+        details.pss_anon = rollup.get_u64("Pss_Anon");
+        details.pss_file = rollup.get_u64("Pss_File");
+        details.pss_shmem = rollup.get_u64("Pss_Shmem");
+        details.shared_clean = rollup.get_u64("Shared_Clean");
+        details.shared_dirty = rollup.get_u64("Shared_Dirty");
+        details.shared = details.shared_clean.zip(details.shared_dirty).map(|(c, d)| c + d);
+        details.private_clean = rollup.get_u64("Private_Clean");
+        details.private_dirty = rollup.get_u64("Private_Dirty");
+        details.anonymous = rollup.get_u64("Anonymous").or(details.anonymous);
+        details.swap = rollup.get_u64("Swap").or(details.swap);
+        details.swap_pss = rollup.get_u64("SwapPss");
+        details.locked = rollup.get_u64("Locked").or(details.locked);
+    }
+
+    Ok(details)
+}
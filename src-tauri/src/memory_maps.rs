@@ -0,0 +1,173 @@
+#[cfg(target_os = "linux")]
+use std::collections::BTreeMap;
+use serde::Serialize;
+
+/// One line of `/proc/<pid>/maps`. Addresses are hex strings because kernel-side mappings
+/// don't fit in a JS number.
+#[derive(Serialize, Clone)]
+pub struct MemoryRegion {
+    pub(crate) start: String,
+    pub(crate) end: String,
+    pub(crate) size: u64,
+    pub(crate) perms: String,
+    pub(crate) offset: u64,
+    pub(crate) device: String,
+    pub(crate) inode: u64,
+    pub(crate) path: Option<String>,
+    pub(crate) deleted: bool,
+}
+
+/// All regions backed by the same file.
+#[derive(Serialize, Clone)]
+pub struct MappedFile {
+    path: String,
+    regions: usize,
+    size: u64,
+    executable: bool,
+    deleted: bool,
+    library: bool,
+}
+
+#[derive(Serialize, Clone)]
+pub struct MemoryMaps {
+    pid: u32,
+    total_size: u64,
+    regions: Vec<MemoryRegion>,
+    files: Vec<MappedFile>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct LibraryUser {
+    pid: u32,
+    name: String,
+    paths: Vec<String>,
+}
+
+#[tauri::command]
+pub async fn memory_maps(pid: u32) -> Result<MemoryMaps, String> {
+    #[cfg(target_os = "linux")]
+    {
+        tauri::async_runtime::spawn_blocking(move || {
+            let regions = read_maps(pid)
+                .map_err(|e| format!("Failed to read memory maps of PID {}: {}", pid, e))?;
+            let files = group_by_file(&regions);
+            let total_size = regions.iter().map(|r| r.size).sum();
+            Ok(MemoryMaps { pid, total_size, regions, files })
+        }).await.map_err(|e| e.to_string())?
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = pid;
+        Err("Unsupported platform".to_string())
+    }
+}
+
+/// Reverse lookup: every process with a file whose path contains `library` mapped,
+/// e.g. `libssl.so` or a full path.
+#[tauri::command]
+pub async fn processes_mapping(library: String) -> Result<Vec<LibraryUser>, String> {
+    #[cfg(target_os = "linux")]
+    {
+        if library.is_empty() {
+            return Err("Library name must not be empty".to_string());
+        }
+        tauri::async_runtime::spawn_blocking(move || {
+            let proc_dir = std::fs::read_dir("/proc").map_err(|e| e.to_string())?;
+            let mut users = vec![];
+            for entry in proc_dir.flatten() {
+                let Some(pid) = entry.file_name().to_str().and_then(|n| n.parse::<u32>().ok()) else {
+                    continue;
+                };
+                let Ok(regions) = read_maps(pid) else {
+                    continue;
+                };
+                let mut paths: Vec<String> = regions
+                    .into_iter()
+                    .filter_map(|r| r.path)
+                    .filter(|p| p.contains(library.as_str()))
+                    .collect();
+                if paths.is_empty() {
+                    continue;
+                }
+                paths.sort();
+                paths.dedup();
+                let name = std::fs::read(format!("/proc/{}/comm", pid))
+                    .map(|comm| String::from_utf8_lossy(&comm).trim_end().to_string())
+                    .unwrap_or_default();
+                users.push(LibraryUser { pid, name, paths });
+            }
+            users.sort_by_key(|u| u.pid);
+            Ok(users)
+        }).await.map_err(|e| e.to_string())?
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = library;
+        Err("Unsupported platform".to_string())
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn read_maps(pid: u32) -> std::io::Result<Vec<MemoryRegion>> {
+    let contents = std::fs::read(format!("/proc/{}/maps", pid))?;
+    Ok(String::from_utf8_lossy(&contents).lines().filter_map(parse_maps_line).collect())
+}
+
+#[cfg(target_os = "linux")]
+fn parse_maps_line(line: &str) -> Option<MemoryRegion> {
+    // address perms offset dev inode [pathname], where pathname may contain spaces.
+    let mut fields = line.splitn(6, ' ');
+    let (start, end) = fields.next()?.split_once('-')?;
+    let perms = fields.next()?;
+    let offset = u64::from_str_radix(fields.next()?, 16).ok()?;
+    let device = fields.next()?;
+    let inode = fields.next()?.parse().ok()?;
+    let path = fields.next().map(str::trim_start).filter(|p| !p.is_empty());
+
+    let size = u64::from_str_radix(end, 16).ok()?.checked_sub(u64::from_str_radix(start, 16).ok()?)?;
+    let deleted = path.is_some_and(|p| p.ends_with(" (deleted)"));
+
+    Some(MemoryRegion {
+        start: start.to_string(),
+        end: end.to_string(),
+        size,
+        perms: perms.to_string(),
+        offset,
+        device: device.to_string(),
+        inode,
+        path: path.map(|p| p.strip_suffix(" (deleted)").unwrap_or(p).to_string()),
+        deleted,
+    })
+}
+
+/// Groups file-backed regions by path. Pseudo paths like `[heap]` are left out.
+#[cfg(target_os = "linux")]
+fn group_by_file(regions: &[MemoryRegion]) -> Vec<MappedFile> {
+    let mut files: BTreeMap<&str, MappedFile> = BTreeMap::new();
+    for region in regions {
+        let Some(path) = region.path.as_deref().filter(|p| p.starts_with('/')) else {
+            continue;
+        };
+        let file = files.entry(path).or_insert_with(|| MappedFile {
+            path: path.to_string(),
+            regions: 0,
+            size: 0,
+            executable: false,
+            deleted: false,
+            library: is_shared_library(path),
+        });
+        file.regions += 1;
+        file.size += region.size;
+        file.executable |= region.perms.contains('x');
+        file.deleted |= region.deleted;
+    }
+    files.into_values().collect()
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn is_shared_library(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    name.ends_with(".so") || name.contains(".so.")
+}
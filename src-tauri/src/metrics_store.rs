@@ -0,0 +1,424 @@
+//! Append-only on-disk history of system and process samples.
+//!
+//! Every sampler tick is appended as one JSON line to an hourly `raw-<hour>.jsonl` segment.
+//! Ticks are also averaged per minute into daily `minute-<day>.jsonl` segments. Segment names
+//! count hours or days since the Unix epoch, so retention deletes whole files. A line cut short
+//! by a crash is skipped when read back.
+
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use sysinfo::System;
+use tauri::Manager;
+use crate::list_process::{now_ms, thread_ids, ProcessInfo};
+
+const RAW_SEGMENT_MS: u64 = 60 * 60 * 1000;
+const ROLLUP_SEGMENT_MS: u64 = 24 * 60 * 60 * 1000;
+const ROLLUP_BUCKET_MS: u64 = 60 * 1000;
+const CONFIG_FILE: &str = "config.json";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct MetricsStoreConfig {
+    enabled: bool,
+    raw_retention_secs: u64,
+    rollup_retention_secs: u64,
+    /// Each sample keeps this many top processes by CPU plus as many by memory.
+    processes_per_sample: usize,
+}
+
+impl Default for MetricsStoreConfig {
+    fn default() -> Self {
+        MetricsStoreConfig {
+            enabled: true,
+            raw_retention_secs: 60 * 60,
+            rollup_retention_secs: 7 * 24 * 60 * 60,
+            processes_per_sample: 25,
+        }
+    }
+}
+
+/// Memory is KiB like `ProcessInfo.memory`; IO rates are bytes per second.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct StoredProcess {
+    pid: u32,
+    name: String,
+    cpu: f32,
+    memory: f64,
+    read_rate: f64,
+    write_rate: f64,
+}
+
+/// One sampler tick, or the average of a minute of them in rollups. System memory is KiB.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct StoredSample {
+    timestamp_ms: u64,
+    cpu_usage: f32,
+    memory_used: u64,
+    swap_used: u64,
+    load_one: f64,
+    processes: Vec<StoredProcess>,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Resolution {
+    Raw,
+    Minute,
+}
+
+/// Running sums for the minute currently being rolled up.
+struct MinuteBucket {
+    start_ms: u64,
+    samples: u64,
+    cpu_usage: f64,
+    memory_used: u64,
+    swap_used: u64,
+    load_one: f64,
+    // pid -> (process sums, number of samples it appeared in)
+    processes: HashMap<u32, (StoredProcess, u64)>,
+}
+
+struct MetricsStore {
+    dir: Option<PathBuf>,
+    config: MetricsStoreConfig,
+    bucket: Option<MinuteBucket>,
+    last_prune_segment: u64,
+}
+
+lazy_static! {
+    static ref STORE: Mutex<MetricsStore> = Mutex::new(MetricsStore {
+        dir: None,
+        config: MetricsStoreConfig::default(),
+        bucket: None,
+        last_prune_segment: 0,
+    });
+}
+
+impl MetricsStore {
+    /// Resolves `<app data>/metrics` and loads the saved config on first use.
+    fn dir(&mut self, app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+        if let Some(dir) = &self.dir {
+            return Ok(dir.clone());
+        }
+        let dir = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("Failed to resolve app data directory: {}", e))?
+            .join("metrics");
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        if let Ok(contents) = fs::read_to_string(dir.join(CONFIG_FILE)) {
+            self.config = serde_json::from_str(&contents).unwrap_or_default();
+        }
+        self.dir = Some(dir.clone());
+        Ok(dir)
+    }
+
+    fn add_to_bucket(&mut self, dir: &Path, sample: &StoredSample) -> Result<(), String> {
+        let start_ms = sample.timestamp_ms - sample.timestamp_ms % ROLLUP_BUCKET_MS;
+        if self.bucket.as_ref().is_some_and(|b| b.start_ms != start_ms) {
+            let finished = self.bucket.take().unwrap();
+            append_line(&segment_path(dir, Resolution::Minute, finished.start_ms), &finished.average())?;
+        }
+
+        let bucket = self.bucket.get_or_insert_with(|| MinuteBucket {
+            start_ms,
+            samples: 0,
+            cpu_usage: 0.0,
+            memory_used: 0,
+            swap_used: 0,
+            load_one: 0.0,
+            processes: HashMap::new(),
+        });
+        bucket.samples += 1;
+        bucket.cpu_usage += sample.cpu_usage as f64;
+        bucket.memory_used += sample.memory_used;
+        bucket.swap_used += sample.swap_used;
+        bucket.load_one += sample.load_one;
+        for process in &sample.processes {
+            let (sum, count) = bucket
+                .processes
+                .entry(process.pid)
+                .or_insert_with(|| (StoredProcess { cpu: 0.0, memory: 0.0, read_rate: 0.0, write_rate: 0.0, ..process.clone() }, 0));
+            sum.name = process.name.clone();
+            sum.cpu += process.cpu;
+            sum.memory += process.memory;
+            sum.read_rate += process.read_rate;
+            sum.write_rate += process.write_rate;
+            *count += 1;
+        }
+        Ok(())
+    }
+
+    /// Deletes segments that ended before their retention window, at most once per hour.
+    fn prune(&mut self, dir: &Path, now_ms: u64) {
+        let segment = now_ms / RAW_SEGMENT_MS;
+        if segment == self.last_prune_segment {
+            return;
+        }
+        self.last_prune_segment = segment;
+
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let Some((resolution, index)) = parse_segment_name(&name) else {
+                continue;
+            };
+            let retention_secs = match resolution {
+                Resolution::Raw => self.config.raw_retention_secs,
+                Resolution::Minute => self.config.rollup_retention_secs,
+            };
+            let expires_ms = index
+                .saturating_add(1)
+                .saturating_mul(segment_length(resolution))
+                .saturating_add(retention_secs.saturating_mul(1000));
+            if expires_ms < now_ms {
+                if let Err(e) = fs::remove_file(entry.path()) {
+                    println!("Failed to remove metrics segment {}: {}", name, e);
+                }
+            }
+        }
+    }
+}
+
+impl MinuteBucket {
+    fn average(&self) -> StoredSample {
+        let n = self.samples.max(1);
+        let mut processes: Vec<StoredProcess> = self
+            .processes
+            .values()
+            .map(|(sum, count)| {
+                let count = *count as f64;
+                StoredProcess {
+                    cpu: (sum.cpu as f64 / count) as f32,
+                    memory: sum.memory / count,
+                    read_rate: sum.read_rate / count,
+                    write_rate: sum.write_rate / count,
+                    ..sum.clone()
+                }
+            })
+            .collect();
+        processes.sort_by_key(|p| p.pid);
+
+        StoredSample {
+            timestamp_ms: self.start_ms,
+            cpu_usage: (self.cpu_usage / n as f64) as f32,
+            memory_used: self.memory_used / n,
+            swap_used: self.swap_used / n,
+            load_one: self.load_one / n as f64,
+            processes,
+        }
+    }
+}
+
+fn segment_length(resolution: Resolution) -> u64 {
+    match resolution {
+        Resolution::Raw => RAW_SEGMENT_MS,
+        Resolution::Minute => ROLLUP_SEGMENT_MS,
+    }
+}
+
+fn segment_path(dir: &Path, resolution: Resolution, timestamp_ms: u64) -> PathBuf {
+    let index = timestamp_ms / segment_length(resolution);
+    match resolution {
+        Resolution::Raw => dir.join(format!("raw-{}.jsonl", index)),
+        Resolution::Minute => dir.join(format!("minute-{}.jsonl", index)),
+    }
+}
+
+fn parse_segment_name(name: &str) -> Option<(Resolution, u64)> {
+    let stem = name.strip_suffix(".jsonl")?;
+    if let Some(index) = stem.strip_prefix("raw-") {
+        return Some((Resolution::Raw, index.parse().ok()?));
+    }
+    Some((Resolution::Minute, stem.strip_prefix("minute-")?.parse().ok()?))
+}
+
+fn append_line(path: &Path, sample: &StoredSample) -> Result<(), String> {
+    let mut line = serde_json::to_string(sample).map_err(|e| e.to_string())?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Samples of `resolution` in `from_ms..=to_ms`, oldest first. Only segments that exist are
+/// visited, so an open-ended range costs no more than the data kept.
+fn read_samples(dir: &Path, resolution: Resolution, from_ms: u64, to_ms: u64) -> Vec<StoredSample> {
+    let length = segment_length(resolution);
+    let indices = from_ms / length..=to_ms / length;
+    let mut segments: Vec<(u64, PathBuf)> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let (kind, index) = parse_segment_name(&entry.file_name().to_string_lossy())?;
+            (kind == resolution && indices.contains(&index)).then(|| (index, entry.path()))
+        })
+        .collect();
+    segments.sort();
+
+    let mut samples = vec![];
+    for (_, path) in segments {
+        let Ok(contents) = fs::read_to_string(path) else {
+            continue;
+        };
+        samples.extend(
+            contents
+                .lines()
+                .filter_map(|line| serde_json::from_str::<StoredSample>(line).ok())
+                .filter(|s| (from_ms..=to_ms).contains(&s.timestamp_ms)),
+        );
+    }
+    samples
+}
+
+/// Builds the stored form of one tick: system totals plus the top processes by CPU and by
+/// memory. The write happens in [`record`], once SYS is unlocked.
+pub(crate) fn sample_from(sys: &System, processes: &[ProcessInfo], timestamp_ms: u64, interval_ms: u64) -> Option<StoredSample> {
+    let store = STORE.lock().unwrap();
+    if !store.config.enabled {
+        return None;
+    }
+    let keep = store.config.processes_per_sample;
+    drop(store);
+
+    let threads = thread_ids(sys);
+    let mut by_cpu: Vec<&ProcessInfo> = processes.iter().filter(|p| !threads.contains(&p.pid)).collect();
+    by_cpu.sort_by(|a, b| b.cpu.total_cmp(&a.cpu));
+    let mut by_memory = by_cpu.clone();
+    by_memory.sort_by(|a, b| b.memory.total_cmp(&a.memory));
+
+    let mut selected: Vec<&ProcessInfo> = by_cpu.into_iter().take(keep).take_while(|p| p.cpu > 0.0).collect();
+    for process in by_memory.into_iter().take(keep) {
+        if !selected.iter().any(|p| p.pid == process.pid) {
+            selected.push(process);
+        }
+    }
+
+    // `read_bytes`/`written_bytes` count since the previous refresh.
+    let per_sec = |bytes: u64| bytes as f64 * 1000.0 / interval_ms.max(1) as f64;
+    Some(StoredSample {
+        timestamp_ms,
+        cpu_usage: sys.global_cpu_usage(),
+        memory_used: sys.used_memory() / 1024,
+        swap_used: sys.used_swap() / 1024,
+        load_one: System::load_average().one,
+        processes: selected
+            .into_iter()
+            .map(|p| StoredProcess {
+                pid: p.pid,
+                name: p.name.clone(),
+                cpu: p.cpu,
+                memory: p.memory,
+                read_rate: per_sec(p.read_bytes),
+                write_rate: per_sec(p.written_bytes),
+            })
+            .collect(),
+    })
+}
+
+/// Appends `sample` to the raw segment and the current minute's rollup.
+pub(crate) fn record(app_handle: &tauri::AppHandle, sample: &StoredSample) -> Result<(), String> {
+    let mut store = STORE.lock().unwrap();
+    let dir = store.dir(app_handle)?;
+    // The saved config is only loaded by `dir`, so check again.
+    if !store.config.enabled {
+        return Ok(());
+    }
+    append_line(&segment_path(&dir, Resolution::Raw, sample.timestamp_ms), sample)?;
+    store.add_to_bucket(&dir, sample)?;
+    store.prune(&dir, sample.timestamp_ms);
+    Ok(())
+}
+
+/// Stored samples between two Unix timestamps in milliseconds. Without a `resolution`, raw
+/// samples are used when the range starts within raw retention and minute averages otherwise.
+#[tauri::command]
+pub async fn query_metrics(
+    app_handle: tauri::AppHandle,
+    from_ms: u64,
+    to_ms: u64,
+    resolution: Option<Resolution>,
+) -> Result<Vec<StoredSample>, String> {
+    if from_ms > to_ms {
+        return Err("from_ms is after to_ms".to_string());
+    }
+    let (dir, raw_retention_secs) = {
+        let mut store = STORE.lock().unwrap();
+        (store.dir(&app_handle)?, store.config.raw_retention_secs)
+    };
+    let resolution = resolution.unwrap_or_else(|| {
+        if from_ms.saturating_add(raw_retention_secs.saturating_mul(1000)) >= now_ms() {
+            Resolution::Raw
+        } else {
+            Resolution::Minute
+        }
+    });
+
+    tauri::async_runtime::spawn_blocking(move || read_samples(&dir, resolution, from_ms, to_ms))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// The stored sample closest to `timestamp_ms`, with its processes sorted by `sort_by`
+/// (`memory`, the default, or `cpu`), e.g. to see what was using memory at 03:12 last night.
+/// Raw samples are preferred; minute averages are used once raw data has expired.
+#[tauri::command]
+pub async fn metrics_at(
+    app_handle: tauri::AppHandle,
+    timestamp_ms: u64,
+    sort_by: Option<String>,
+    limit: Option<usize>,
+) -> Result<StoredSample, String> {
+    let dir = STORE.lock().unwrap().dir(&app_handle)?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let nearest = |resolution: Resolution, slack_ms: u64| {
+            read_samples(&dir, resolution, timestamp_ms.saturating_sub(slack_ms), timestamp_ms.saturating_add(slack_ms))
+                .into_iter()
+                .min_by_key(|s| s.timestamp_ms.abs_diff(timestamp_ms))
+        };
+        let mut sample = nearest(Resolution::Raw, ROLLUP_BUCKET_MS)
+            .or_else(|| nearest(Resolution::Minute, 2 * ROLLUP_BUCKET_MS))
+            .ok_or_else(|| "No stored metrics near that time".to_string())?;
+
+        match sort_by.as_deref().unwrap_or("memory") {
+            "memory" => sample.processes.sort_by(|a, b| b.memory.total_cmp(&a.memory)),
+            "cpu" => sample.processes.sort_by(|a, b| b.cpu.total_cmp(&a.cpu)),
+            other => return Err(format!("Cannot sort stored processes by '{}'", other)),
+        }
+        if let Some(limit) = limit {
+            sample.processes.truncate(limit);
+        }
+        Ok(sample)
+    }).await.map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub fn metrics_store_config(app_handle: tauri::AppHandle) -> Result<MetricsStoreConfig, String> {
+    let mut store = STORE.lock().unwrap();
+    store.dir(&app_handle)?;
+    Ok(store.config.clone())
+}
+
+/// Saves the config next to the segments. Expired segments are pruned on the next sample.
+#[tauri::command]
+pub fn set_metrics_store_config(app_handle: tauri::AppHandle, config: MetricsStoreConfig) -> Result<MetricsStoreConfig, String> {
+    let mut store = STORE.lock().unwrap();
+    let dir = store.dir(&app_handle)?;
+    let contents = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+    fs::write(dir.join(CONFIG_FILE), contents).map_err(|e| format!("Failed to save metrics config: {}", e))?;
+    store.config = config.clone();
+    store.last_prune_segment = 0;
+    Ok(config)
+}
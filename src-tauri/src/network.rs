@@ -0,0 +1,127 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Instant;
+use lazy_static::lazy_static;
+use serde::Serialize;
+use sysinfo::Networks;
+use crate::list_process::{now_ms, sampler_running};
+
+/// Traffic on one interface. Rates are per second since the previous sample and `None` on
+/// the first one; the `total_*` counters are since the interface came up.
+#[derive(Serialize, Clone)]
+pub struct InterfaceStats {
+    name: String,
+    mac_address: String,
+    mtu: u64,
+    addresses: Vec<String>,
+    rx_bytes_per_sec: Option<f64>,
+    tx_bytes_per_sec: Option<f64>,
+    rx_packets_per_sec: Option<f64>,
+    tx_packets_per_sec: Option<f64>,
+    rx_errors_per_sec: Option<f64>,
+    tx_errors_per_sec: Option<f64>,
+    total_rx_bytes: u64,
+    total_tx_bytes: u64,
+    total_rx_errors: u64,
+    total_tx_errors: u64,
+}
+
+/// `tcp_states` counts TCP sockets over IPv4 and IPv6 by state, e.g. `ESTABLISHED`, in
+/// this process's network namespace. It is empty off Linux.
+#[derive(Serialize, Clone)]
+pub struct NetworkSample {
+    timestamp_ms: u64,
+    interfaces: Vec<InterfaceStats>,
+    tcp_states: BTreeMap<String, usize>,
+}
+
+struct NetworkState {
+    networks: Networks,
+    last_refresh: Option<Instant>,
+}
+
+lazy_static! {
+    static ref NETWORKS: Mutex<NetworkState> = Mutex::new(NetworkState {
+        networks: Networks::new(),
+        last_refresh: None,
+    });
+    static ref LAST_SAMPLE: Mutex<Option<NetworkSample>> = Mutex::new(None);
+}
+
+#[cfg(target_os = "linux")]
+fn tcp_state_counts() -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for socket in crate::sockets::read_inet_sockets() {
+        if socket.protocol.starts_with("tcp") {
+            *counts.entry(socket.state).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+#[cfg(not(target_os = "linux"))]
+fn tcp_state_counts() -> BTreeMap<String, usize> {
+    BTreeMap::new()
+}
+
+/// Refreshes the interface counters and advances the rate baseline.
+pub(crate) fn sample_network() -> NetworkSample {
+    let mut state = NETWORKS.lock().unwrap();
+    state.networks.refresh(true);
+    let now = Instant::now();
+    let secs = state
+        .last_refresh
+        .replace(now)
+        .map(|then| now.duration_since(then).as_secs_f64())
+        .filter(|secs| *secs > 0.0);
+    let rate = |delta: u64| secs.map(|secs| delta as f64 / secs);
+
+    let mut interfaces: Vec<InterfaceStats> = state
+        .networks
+        .list()
+        .iter()
+        .map(|(name, data)| InterfaceStats {
+            name: name.clone(),
+            mac_address: data.mac_address().to_string(),
+            mtu: data.mtu(),
+            addresses: data
+                .ip_networks()
+                .iter()
+                .map(|ip| format!("{}/{}", ip.addr, ip.prefix))
+                .collect(),
+            rx_bytes_per_sec: rate(data.received()),
+            tx_bytes_per_sec: rate(data.transmitted()),
+            rx_packets_per_sec: rate(data.packets_received()),
+            tx_packets_per_sec: rate(data.packets_transmitted()),
+            rx_errors_per_sec: rate(data.errors_on_received()),
+            tx_errors_per_sec: rate(data.errors_on_transmitted()),
+            total_rx_bytes: data.total_received(),
+            total_tx_bytes: data.total_transmitted(),
+            total_rx_errors: data.total_errors_on_received(),
+            total_tx_errors: data.total_errors_on_transmitted(),
+        })
+        .collect();
+    drop(state);
+    interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let sample = NetworkSample {
+        timestamp_ms: now_ms(),
+        interfaces,
+        tcp_states: tcp_state_counts(),
+    };
+    *LAST_SAMPLE.lock().unwrap() = Some(sample.clone());
+    sample
+}
+
+/// Interface traffic and addresses plus TCP connection counts by state.
+#[tauri::command]
+pub async fn network_stats() -> Result<NetworkSample, String> {
+    if sampler_running() {
+        if let Some(sample) = LAST_SAMPLE.lock().unwrap().clone() {
+            return Ok(sample);
+        }
+    }
+    tauri::async_runtime::spawn_blocking(sample_network)
+        .await
+        .map_err(|e| e.to_string())
+}
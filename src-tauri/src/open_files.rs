@@ -0,0 +1,160 @@
+use std::collections::BTreeMap;
+use serde::Serialize;
+
+#[derive(Serialize, Clone)]
+pub struct FdEntry {
+    fd: u32,
+    kind: String,
+    target: String,
+    deleted: bool,
+    inode: Option<u64>,
+    access: Option<String>,
+    flags: Vec<String>,
+    pos: Option<u64>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct OpenFiles {
+    pid: u32,
+    total: usize,
+    counts: BTreeMap<String, usize>,
+    soft_limit: Option<u64>,
+    hard_limit: Option<u64>,
+    fds: Vec<FdEntry>,
+}
+
+/// Every open descriptor of `pid` from `/proc/<pid>/fd` and `fdinfo`, with counts by kind
+/// and the current `RLIMIT_NOFILE` so leaks can be compared against the cap.
+#[tauri::command]
+pub async fn list_open_files(pid: u32) -> Result<OpenFiles, String> {
+    #[cfg(target_os = "linux")]
+    {
+        tauri::async_runtime::spawn_blocking(move || list_open_files_linux(pid))
+            .await
+            .map_err(|e| e.to_string())?
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = pid;
+        Err("Unsupported platform".to_string())
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn list_open_files_linux(pid: u32) -> Result<OpenFiles, String> {
+    let entries = std::fs::read_dir(format!("/proc/{}/fd", pid))
+        .map_err(|e| format!("Failed to read descriptors of PID {}: {}", pid, e))?;
+
+    let mut fds = vec![];
+    for entry in entries.flatten() {
+        let Some(fd) = entry.file_name().to_str().and_then(|n| n.parse::<u32>().ok()) else {
+            continue;
+        };
+        // The descriptor may be closed between listing the directory and reading the link.
+        let Ok(target) = std::fs::read_link(entry.path()) else {
+            continue;
+        };
+        let target = target.to_string_lossy().into_owned();
+        let (kind, inode, deleted) = classify_target(&target);
+
+        let fdinfo = std::fs::read_to_string(format!("/proc/{}/fdinfo/{}", pid, fd)).unwrap_or_default();
+        let field = |name: &str| {
+            fdinfo
+                .lines()
+                .find_map(|line| line.strip_prefix(name)?.strip_prefix(':'))
+                .map(str::trim)
+        };
+        let raw_flags = field("flags").and_then(|f| u32::from_str_radix(f, 8).ok());
+
+        fds.push(FdEntry {
+            fd,
+            kind: kind.to_string(),
+            target,
+            deleted,
+            inode,
+            access: raw_flags.map(|f| access_mode(f).to_string()),
+            flags: raw_flags.map(flag_names).unwrap_or_default(),
+            pos: field("pos").and_then(|p| p.parse().ok()),
+        });
+    }
+    fds.sort_by_key(|f| f.fd);
+
+    let mut counts = BTreeMap::new();
+    for fd in &fds {
+        *counts.entry(fd.kind.clone()).or_insert(0) += 1;
+    }
+    let (soft_limit, hard_limit) = open_files_limit(pid);
+
+    Ok(OpenFiles { pid, total: fds.len(), counts, soft_limit, hard_limit, fds })
+}
+
+#[cfg(target_os = "linux")]
+/// Kind, inode and deleted flag for an fd link target such as `socket:[1234]`.
+pub(crate) fn classify_target(target: &str) -> (&'static str, Option<u64>, bool) {
+    let inode = |prefix: &str| {
+        target.strip_prefix(prefix)?.strip_prefix('[')?.strip_suffix(']')?.parse().ok()
+    };
+
+    if let Some(inode) = inode("socket:") {
+        ("socket", Some(inode), false)
+    } else if let Some(inode) = inode("pipe:") {
+        ("pipe", Some(inode), false)
+    } else if target.starts_with("anon_inode:") {
+        ("anon_inode", None, false)
+    } else if target.ends_with(" (deleted)") {
+        ("deleted", None, true)
+    } else if target.starts_with('/') {
+        ("file", None, false)
+    } else {
+        ("other", None, false)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn access_mode(flags: u32) -> &'static str {
+    match flags as i32 & libc::O_ACCMODE {
+        libc::O_RDONLY => "read",
+        libc::O_WRONLY => "write",
+        libc::O_RDWR => "read_write",
+        _ => "unknown",
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn flag_names(flags: u32) -> Vec<String> {
+    let flags = flags as i32;
+    [
+        (libc::O_APPEND, "O_APPEND"),
+        (libc::O_NONBLOCK, "O_NONBLOCK"),
+        (libc::O_CLOEXEC, "O_CLOEXEC"),
+        (libc::O_DIRECT, "O_DIRECT"),
+        (libc::O_DIRECTORY, "O_DIRECTORY"),
+        (libc::O_NOATIME, "O_NOATIME"),
+        (libc::O_PATH, "O_PATH"),
+        (libc::O_LARGEFILE, "O_LARGEFILE"),
+    ]
+    .iter()
+    .filter(|(bit, _)| *bit != 0 && flags & bit == *bit)
+    .map(|(_, name)| name.to_string())
+    // O_SYNC contains the O_DSYNC bit, so check it before falling back to O_DSYNC.
+    .chain(if flags & libc::O_SYNC == libc::O_SYNC {
+        Some("O_SYNC".to_string())
+    } else if flags & libc::O_DSYNC == libc::O_DSYNC {
+        Some("O_DSYNC".to_string())
+    } else {
+        None
+    })
+    .collect()
+}
+
+#[cfg(target_os = "linux")]
+/// Soft and hard "Max open files" from `/proc/<pid>/limits`; `None` means unlimited.
+fn open_files_limit(pid: u32) -> (Option<u64>, Option<u64>) {
+    let limits = std::fs::read_to_string(format!("/proc/{}/limits", pid)).unwrap_or_default();
+    let Some(line) = limits.lines().find(|l| l.starts_with("Max open files")) else {
+        return (None, None);
+    };
+    let mut values = line["Max open files".len()..].split_whitespace().map(|v| v.parse().ok());
+    (values.next().flatten(), values.next().flatten())
+}
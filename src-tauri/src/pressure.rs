@@ -0,0 +1,151 @@
+#[cfg(target_os = "linux")]
+use std::sync::Mutex;
+#[cfg(target_os = "linux")]
+use lazy_static::lazy_static;
+use serde::Serialize;
+#[cfg(target_os = "linux")]
+use crate::list_process::{now_ms, sampler_running};
+
+/// One `some` or `full` line of a `/proc/pressure` file. The averages are the percentage of
+/// wall time stalled over the last 10, 60 and 300 seconds; `total_us` is cumulative.
+#[derive(Serialize, Clone, Default)]
+pub struct PressureLine {
+    avg10: f64,
+    avg60: f64,
+    avg300: f64,
+    total_us: u64,
+}
+
+/// `some`: at least one task stalled on the resource. `full`: all non-idle tasks stalled at
+/// once. Kernels before 5.13 have no `full` line for CPU.
+#[derive(Serialize, Clone)]
+pub struct Pressure {
+    some: PressureLine,
+    full: Option<PressureLine>,
+}
+
+/// Share of CPU time in each state since the previous sample, in percent of all cores.
+#[derive(Serialize, Clone)]
+pub struct CpuBreakdown {
+    user: f64,
+    nice: f64,
+    system: f64,
+    idle: f64,
+    iowait: f64,
+    irq: f64,
+    softirq: f64,
+    steal: f64,
+    guest: f64,
+}
+
+/// Pressure stall information is missing when the kernel lacks PSI (or booted with
+/// `psi=0`); `cpu_time` is `None` until two samples have been taken.
+#[derive(Serialize, Clone)]
+pub struct SystemPressure {
+    timestamp_ms: u64,
+    cpu: Option<Pressure>,
+    memory: Option<Pressure>,
+    io: Option<Pressure>,
+    cpu_time: Option<CpuBreakdown>,
+}
+
+#[cfg(target_os = "linux")]
+lazy_static! {
+    static ref PREVIOUS_TICKS: Mutex<Option<crate::procfs::CpuTicks>> = Mutex::new(None);
+    static ref LAST_PRESSURE: Mutex<Option<SystemPressure>> = Mutex::new(None);
+}
+
+#[cfg(target_os = "linux")]
+fn parse_pressure(contents: &str) -> Option<Pressure> {
+    let mut some = None;
+    let mut full = None;
+    for line in contents.lines() {
+        let mut parts = line.split_whitespace();
+        let kind = parts.next();
+        let mut values = PressureLine::default();
+        for part in parts {
+            match part.split_once('=') {
+                Some(("avg10", v)) => values.avg10 = v.parse().ok()?,
+                Some(("avg60", v)) => values.avg60 = v.parse().ok()?,
+                Some(("avg300", v)) => values.avg300 = v.parse().ok()?,
+                Some(("total", v)) => values.total_us = v.parse().ok()?,
+                _ => {}
+            }
+        }
+        match kind {
+            Some("some") => some = Some(values),
+            Some("full") => full = Some(values),
+            _ => {}
+        }
+    }
+    Some(Pressure { some: some?, full })
+}
+
+#[cfg(target_os = "linux")]
+fn read_pressure(resource: &str) -> Option<Pressure> {
+    parse_pressure(&std::fs::read_to_string(format!("/proc/pressure/{}", resource)).ok()?)
+}
+
+#[cfg(target_os = "linux")]
+fn cpu_breakdown(before: &crate::procfs::CpuTicks, now: &crate::procfs::CpuTicks) -> Option<CpuBreakdown> {
+    let total = now.total().saturating_sub(before.total());
+    if total == 0 {
+        return None;
+    }
+    let share = |now: u64, before: u64| now.saturating_sub(before) as f64 * 100.0 / total as f64;
+
+    Some(CpuBreakdown {
+        user: share(now.user, before.user),
+        nice: share(now.nice, before.nice),
+        system: share(now.system, before.system),
+        idle: share(now.idle, before.idle),
+        iowait: share(now.iowait, before.iowait),
+        irq: share(now.irq, before.irq),
+        softirq: share(now.softirq, before.softirq),
+        steal: share(now.steal, before.steal),
+        guest: share(now.guest, before.guest),
+    })
+}
+
+/// Reads PSI and advances the CPU time baseline.
+#[cfg(target_os = "linux")]
+pub(crate) fn sample_pressure() -> SystemPressure {
+    let ticks = crate::procfs::read_kernel_stat().and_then(|stat| stat.cpu);
+    let cpu_time = {
+        let mut previous = PREVIOUS_TICKS.lock().unwrap();
+        let before = std::mem::replace(&mut *previous, ticks);
+        before.zip(ticks).and_then(|(before, now)| cpu_breakdown(&before, &now))
+    };
+
+    let pressure = SystemPressure {
+        timestamp_ms: now_ms(),
+        cpu: read_pressure("cpu"),
+        memory: read_pressure("memory"),
+        io: read_pressure("io"),
+        cpu_time,
+    };
+    *LAST_PRESSURE.lock().unwrap() = Some(pressure.clone());
+    pressure
+}
+
+/// Stall averages for CPU, memory and IO. The CPU time breakdown is `None` until there is a
+/// previous sample to compare against.
+#[tauri::command]
+pub async fn system_pressure() -> Result<SystemPressure, String> {
+    #[cfg(target_os = "linux")]
+    {
+        if sampler_running() {
+            if let Some(pressure) = LAST_PRESSURE.lock().unwrap().clone() {
+                return Ok(pressure);
+            }
+        }
+        tauri::async_runtime::spawn_blocking(sample_pressure)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        Err("Unsupported platform".to_string())
+    }
+}
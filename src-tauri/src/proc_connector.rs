@@ -0,0 +1,380 @@
+//! Optional lifecycle event source based on the kernel proc connector (`NETLINK_CONNECTOR`,
+//! `CN_IDX_PROC`). Unlike polling it sees every fork, exec and exit, including processes that
+//! live for less than one sampler interval. Subscribing needs `CAP_NET_ADMIN`; without it the
+//! sampler's polling keeps producing lifecycle events.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use lazy_static::lazy_static;
+
+lazy_static! {
+    /// Stop flag of the running listener thread.
+    static ref STOP: Mutex<Option<Arc<AtomicBool>>> = Mutex::new(None);
+}
+
+/// Starts or stops the proc connector. Returns the lifecycle source now in use; enabling fails
+/// with the reason when the kernel refuses, in which case polling stays in charge.
+#[tauri::command]
+pub fn set_proc_connector(app_handle: tauri::AppHandle, enabled: bool) -> Result<String, String> {
+    let mut stop = STOP.lock().unwrap();
+    if !enabled {
+        if let Some(flag) = stop.take() {
+            flag.store(true, Ordering::Relaxed);
+        }
+        return Ok("poll".to_string());
+    }
+    if stop.is_some() {
+        return Ok("netlink".to_string());
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let socket = linux::ProcConnector::open()
+            .map_err(|e| format!("Proc connector unavailable ({}), using polling", e))?;
+        let flag = Arc::new(AtomicBool::new(false));
+        *stop = Some(flag.clone());
+        crate::lifecycle::NETLINK_ACTIVE.store(true, Ordering::Relaxed);
+        std::thread::spawn(move || {
+            linux::run(socket, &app_handle, &flag);
+
+            // Hand back to polling unless a new listener already replaced this one.
+            let mut stop = STOP.lock().unwrap();
+            if stop.as_ref().is_some_and(|current| Arc::ptr_eq(current, &flag)) {
+                *stop = None;
+            }
+            if stop.is_none() {
+                crate::lifecycle::NETLINK_ACTIVE.store(false, Ordering::Relaxed);
+            }
+        });
+        Ok("netlink".to_string())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = app_handle;
+        Err("Unsupported platform".to_string())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::collections::HashMap;
+    use std::io;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::{Duration, Instant};
+    use crate::lifecycle::{last_known, publish, EventSource, LifecycleEvent, LifecycleKind};
+    use crate::list_process::now_ms;
+
+    // From <linux/connector.h> and <linux/cn_proc.h>.
+    const CN_IDX_PROC: u32 = 1;
+    const CN_VAL_PROC: u32 = 1;
+    const PROC_CN_MCAST_LISTEN: u32 = 1;
+    const PROC_EVENT_FORK: u32 = 0x1;
+    const PROC_EVENT_EXEC: u32 = 0x2;
+    const PROC_EVENT_UID: u32 = 0x4;
+    const PROC_EVENT_COMM: u32 = 0x200;
+    const PROC_EVENT_EXIT: u32 = 0x8000_0000;
+
+    const NLMSG_HDR_LEN: usize = 16;
+    const CN_MSG_LEN: usize = 20;
+    // `what`, `cpu` and the timestamp precede the event data.
+    const PROC_EVENT_HDR_LEN: usize = 16;
+
+    /// Events are written out in batches so a fork storm doesn't open the timeline per event.
+    const FLUSH_INTERVAL: Duration = Duration::from_millis(250);
+    const MAX_BATCH: usize = 512;
+    const MAX_LIVE: usize = 65536;
+
+    pub(super) struct ProcConnector(OwnedFd);
+
+    impl ProcConnector {
+        pub(super) fn open() -> io::Result<Self> {
+            let fd = unsafe { libc::socket(libc::AF_NETLINK, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, libc::NETLINK_CONNECTOR) };
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let socket = ProcConnector(unsafe { OwnedFd::from_raw_fd(fd) });
+
+            let mut addr: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+            addr.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+            addr.nl_groups = CN_IDX_PROC;
+            let bound = unsafe {
+                libc::bind(
+                    fd,
+                    &addr as *const libc::sockaddr_nl as *const libc::sockaddr,
+                    std::mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+                )
+            };
+            if bound < 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            // Wake up periodically so the stop flag and pending batches are seen.
+            let timeout = libc::timeval { tv_sec: 0, tv_usec: FLUSH_INTERVAL.as_micros() as libc::suseconds_t };
+            unsafe {
+                libc::setsockopt(
+                    fd,
+                    libc::SOL_SOCKET,
+                    libc::SO_RCVTIMEO,
+                    &timeout as *const libc::timeval as *const libc::c_void,
+                    std::mem::size_of::<libc::timeval>() as libc::socklen_t,
+                );
+            }
+
+            socket.send_op(PROC_CN_MCAST_LISTEN)?;
+            Ok(socket)
+        }
+
+        /// nlmsghdr + cn_msg + the u32 multicast op.
+        fn send_op(&self, op: u32) -> io::Result<()> {
+            let total = NLMSG_HDR_LEN + CN_MSG_LEN + 4;
+            let mut message = Vec::with_capacity(total);
+            message.extend_from_slice(&(total as u32).to_ne_bytes());
+            message.extend_from_slice(&(libc::NLMSG_DONE as u16).to_ne_bytes());
+            message.extend_from_slice(&0u16.to_ne_bytes());
+            message.extend_from_slice(&0u32.to_ne_bytes());
+            message.extend_from_slice(&std::process::id().to_ne_bytes());
+            message.extend_from_slice(&CN_IDX_PROC.to_ne_bytes());
+            message.extend_from_slice(&CN_VAL_PROC.to_ne_bytes());
+            message.extend_from_slice(&0u32.to_ne_bytes());
+            message.extend_from_slice(&0u32.to_ne_bytes());
+            message.extend_from_slice(&4u16.to_ne_bytes());
+            message.extend_from_slice(&0u16.to_ne_bytes());
+            message.extend_from_slice(&op.to_ne_bytes());
+
+            let sent = unsafe { libc::send(self.0.as_raw_fd(), message.as_ptr() as *const libc::c_void, message.len(), 0) };
+            if sent < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        }
+
+        fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = unsafe { libc::recv(self.0.as_raw_fd(), buf.as_mut_ptr() as *mut libc::c_void, buf.len(), 0) };
+            if n < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(n as usize)
+        }
+    }
+
+    /// One decoded `proc_event`. PIDs are the kernel's: `pid` is the thread, `tgid` the process.
+    enum ProcEvent {
+        Fork { parent_tgid: u32, child_pid: u32, child_tgid: u32 },
+        Exec { tgid: u32 },
+        Uid { pid: u32, tgid: u32, ruid: u32, euid: u32 },
+        Comm { pid: u32, tgid: u32, comm: String },
+        Exit { pid: u32, tgid: u32, exit_code: u32, parent_tgid: Option<u32> },
+    }
+
+    fn u32_at(data: &[u8], offset: usize) -> Option<u32> {
+        Some(u32::from_ne_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+    }
+
+    fn parse_event(event: &[u8]) -> Option<ProcEvent> {
+        let what = u32_at(event, 0)?;
+        let data = event.get(PROC_EVENT_HDR_LEN..)?;
+        let field = |n: usize| u32_at(data, n * 4);
+
+        Some(match what {
+            PROC_EVENT_FORK => ProcEvent::Fork { parent_tgid: field(1)?, child_pid: field(2)?, child_tgid: field(3)? },
+            PROC_EVENT_EXEC => ProcEvent::Exec { tgid: field(1)? },
+            PROC_EVENT_UID => ProcEvent::Uid { pid: field(0)?, tgid: field(1)?, ruid: field(2)?, euid: field(3)? },
+            PROC_EVENT_COMM => {
+                let comm = data.get(8..24)?;
+                let end = comm.iter().position(|b| *b == 0).unwrap_or(comm.len());
+                ProcEvent::Comm { pid: field(0)?, tgid: field(1)?, comm: String::from_utf8_lossy(&comm[..end]).to_string() }
+            }
+            // parent_pid/parent_tgid were appended in Linux 4.18.
+            PROC_EVENT_EXIT => ProcEvent::Exit { pid: field(0)?, tgid: field(1)?, exit_code: field(2)?, parent_tgid: field(5) },
+            _ => return None,
+        })
+    }
+
+    /// Splits a datagram into netlink messages and yields the `proc_event` of each.
+    fn events_in(buf: &[u8]) -> Vec<ProcEvent> {
+        let mut events = vec![];
+        let mut offset = 0;
+        while let Some(len) = u32_at(buf, offset).map(|len| len as usize) {
+            if len < NLMSG_HDR_LEN || offset + len > buf.len() {
+                break;
+            }
+            let kind = buf.get(offset + 4..offset + 6).map(|b| u16::from_ne_bytes([b[0], b[1]]));
+            if kind == Some(libc::NLMSG_DONE as u16) {
+                if let Some(event) = buf.get(offset + NLMSG_HDR_LEN + CN_MSG_LEN..offset + len).and_then(parse_event) {
+                    events.push(event);
+                }
+            }
+            offset += (len + 3) & !3;
+        }
+        events
+    }
+
+    fn blank_event(kind: LifecycleKind, pid: u32) -> LifecycleEvent {
+        LifecycleEvent {
+            kind,
+            source: EventSource::Netlink,
+            timestamp_ms: now_ms(),
+            pid,
+            name: String::new(),
+            cmd: String::new(),
+            exe: String::new(),
+            parent_pid: None,
+            user: None,
+            runtime_secs: None,
+            cpu: None,
+            memory: None,
+            total_read_bytes: None,
+            total_written_bytes: None,
+            num_threads: None,
+            exit_code: None,
+            signal: None,
+            uid: None,
+            euid: None,
+        }
+    }
+
+    /// Fills name, cmd, exe and parent from `/proc`, falling back to the last poll. A process
+    /// that already exited leaves them as they were.
+    fn describe(event: &mut LifecycleEvent) {
+        let pid = event.pid;
+        if let Some(status) = crate::procfs::read_status(pid) {
+            event.name = status.get("Name").unwrap_or_default().to_string();
+            event.parent_pid = status.get_u64("PPid").map(|ppid| ppid as u32).filter(|ppid| *ppid != 0);
+            event.uid = status.get_u64("Uid").map(|uid| uid as u32);
+            if let Ok(cmdline) = std::fs::read(format!("/proc/{}/cmdline", pid)) {
+                event.cmd = cmdline
+                    .split(|b| *b == 0)
+                    .filter(|arg| !arg.is_empty())
+                    .map(String::from_utf8_lossy)
+                    .collect::<Vec<_>>()
+                    .join(" ");
+            }
+            if let Ok(exe) = std::fs::read_link(format!("/proc/{}/exe", pid)) {
+                event.exe = exe.to_string_lossy().to_string();
+            }
+        } else if let Some(process) = last_known(pid) {
+            event.name = process.name;
+            event.cmd = process.cmd;
+            event.exe = process.exe;
+            event.parent_pid = process.parent_pid;
+        }
+    }
+
+    /// Processes seen by the connector, so an exit can be reported with the details and
+    /// runtime of a process that never made it into a poll.
+    struct Live {
+        started: HashMap<u32, (Instant, LifecycleEvent)>,
+    }
+
+    impl Live {
+        fn convert(&mut self, event: ProcEvent) -> Option<LifecycleEvent> {
+            match event {
+                // Thread creation has child_pid != child_tgid.
+                ProcEvent::Fork { parent_tgid, child_pid, child_tgid } if child_pid == child_tgid => {
+                    let mut lifecycle = blank_event(LifecycleKind::Started, child_tgid);
+                    describe(&mut lifecycle);
+                    lifecycle.parent_pid = Some(parent_tgid);
+                    lifecycle.runtime_secs = Some(0);
+                    lifecycle.uid = None;
+                    // Exits lost to an overrun would otherwise keep entries forever.
+                    if self.started.len() >= MAX_LIVE {
+                        self.started.retain(|pid, _| std::path::Path::new(&format!("/proc/{}", pid)).exists());
+                    }
+                    self.started.insert(child_tgid, (Instant::now(), lifecycle.clone()));
+                    Some(lifecycle)
+                }
+                ProcEvent::Fork { .. } => None,
+                ProcEvent::Exec { tgid } => {
+                    let mut lifecycle = blank_event(LifecycleKind::Exec, tgid);
+                    describe(&mut lifecycle);
+                    lifecycle.uid = None;
+                    // Empty when the process exited before /proc could be read.
+                    if let Some((_, started)) = self.started.get_mut(&tgid) {
+                        if lifecycle.name.is_empty() {
+                            lifecycle.name = started.name.clone();
+                            lifecycle.parent_pid = started.parent_pid;
+                        } else {
+                            started.name = lifecycle.name.clone();
+                            started.cmd = lifecycle.cmd.clone();
+                            started.exe = lifecycle.exe.clone();
+                        }
+                    }
+                    Some(lifecycle)
+                }
+                ProcEvent::Uid { pid, tgid, ruid, euid } if pid == tgid => {
+                    let mut lifecycle = blank_event(LifecycleKind::UidChanged, tgid);
+                    describe(&mut lifecycle);
+                    lifecycle.uid = Some(ruid);
+                    lifecycle.euid = Some(euid);
+                    Some(lifecycle)
+                }
+                // Threads rename themselves all the time; only report the main thread.
+                ProcEvent::Comm { pid, tgid, comm } if pid == tgid => {
+                    let mut lifecycle = blank_event(LifecycleKind::CommChanged, tgid);
+                    describe(&mut lifecycle);
+                    lifecycle.name = comm;
+                    lifecycle.uid = None;
+                    Some(lifecycle)
+                }
+                ProcEvent::Exit { pid, tgid, exit_code, parent_tgid } if pid == tgid => {
+                    let seen = self.started.remove(&tgid);
+                    let mut lifecycle = match (last_known(tgid), &seen) {
+                        (Some(process), _) => LifecycleEvent::new(LifecycleKind::Exited, now_ms(), &process),
+                        (None, Some((_, started))) => LifecycleEvent { kind: LifecycleKind::Exited, timestamp_ms: now_ms(), ..started.clone() },
+                        (None, None) => blank_event(LifecycleKind::Exited, tgid),
+                    };
+                    lifecycle.source = EventSource::Netlink;
+                    if let Some((at, _)) = seen {
+                        lifecycle.runtime_secs = Some(at.elapsed().as_secs());
+                    }
+                    lifecycle.parent_pid = parent_tgid.filter(|ppid| *ppid != 0).or(lifecycle.parent_pid);
+                    // Same encoding as a wait(2) status.
+                    let status = exit_code as i32;
+                    if libc::WIFSIGNALED(status) {
+                        lifecycle.signal = Some(libc::WTERMSIG(status));
+                    } else {
+                        lifecycle.exit_code = Some(libc::WEXITSTATUS(status));
+                    }
+                    Some(lifecycle)
+                }
+                ProcEvent::Uid { .. } | ProcEvent::Comm { .. } | ProcEvent::Exit { .. } => None,
+            }
+        }
+    }
+
+    pub(super) fn run(socket: ProcConnector, app_handle: &tauri::AppHandle, stop: &AtomicBool) {
+        let mut live = Live { started: HashMap::new() };
+        let mut batch = vec![];
+        let mut last_flush = Instant::now();
+        let mut buf = vec![0u8; 16 * 1024];
+
+        while !stop.load(Ordering::Relaxed) {
+            match socket.recv(&mut buf) {
+                Ok(n) => batch.extend(events_in(&buf[..n]).into_iter().filter_map(|e| live.convert(e))),
+                Err(e) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut | io::ErrorKind::Interrupted) => {}
+                // ENOBUFS: the kernel dropped events because we fell behind. Keep listening.
+                Err(e) if e.raw_os_error() == Some(libc::ENOBUFS) => {
+                    println!("Proc connector overrun, some lifecycle events were lost");
+                }
+                Err(e) => {
+                    println!("Proc connector failed: {}", e);
+                    break;
+                }
+            }
+
+            if !batch.is_empty() && (batch.len() >= MAX_BATCH || last_flush.elapsed() >= FLUSH_INTERVAL) {
+                if let Err(e) = publish(app_handle, &batch) {
+                    println!("Failed to record lifecycle events: {}", e);
+                }
+                batch.clear();
+                last_flush = Instant::now();
+            }
+        }
+
+        if let Err(e) = publish(app_handle, &batch) {
+            println!("Failed to record lifecycle events: {}", e);
+        }
+    }
+}
@@ -0,0 +1,291 @@
+//! Parsers for the `/proc/<pid>` files that sysinfo doesn't expose.
+
+use std::collections::HashMap;
+use std::fs;
+
+/// The subset of `/proc/<pid>/stat` we use.
+pub(crate) struct ProcStat {
+    pub comm: String,
+    pub state: char,
+    pub tty_nr: i32,
+    pub minflt: u64,
+    pub majflt: u64,
+    pub utime: u64,
+    pub stime: u64,
+    pub nice: i32,
+    pub num_threads: u32,
+    pub starttime: u64,
+    pub processor: Option<u32>,
+}
+
+pub(crate) fn read_stat(pid: u32) -> Option<ProcStat> {
+    read_stat_file(&format!("/proc/{}/stat", pid))
+}
+
+/// Same as [`read_stat`] for `/proc/<pid>/task/<tid>/stat`.
+pub(crate) fn read_task_stat(pid: u32, tid: u32) -> Option<ProcStat> {
+    read_stat_file(&format!("/proc/{}/task/{}/stat", pid, tid))
+}
+
+fn read_stat_file(path: &str) -> Option<ProcStat> {
+    // comm is arbitrary bytes, so don't let one odd name fail the whole read.
+    parse_stat(&String::from_utf8_lossy(&fs::read(path).ok()?))
+}
+
+pub(crate) fn parse_stat(contents: &str) -> Option<ProcStat> {
+    // comm can contain spaces and parentheses, so fields are counted from the last ')'.
+    let comm_end = contents.rfind(')')?;
+    let comm = &contents[contents.find('(')? + 1..comm_end];
+    let rest = &contents[comm_end + 1..];
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+    // fields[0] is the state, i.e. field 3 in proc(5).
+    let field = |n: usize| fields.get(n - 3).copied();
+
+    Some(ProcStat {
+        comm: comm.to_string(),
+        state: field(3)?.chars().next()?,
+        tty_nr: field(7)?.parse().ok()?,
+        minflt: field(10)?.parse().ok()?,
+        majflt: field(12)?.parse().ok()?,
+        utime: field(14)?.parse().ok()?,
+        stime: field(15)?.parse().ok()?,
+        nice: field(19)?.parse().ok()?,
+        num_threads: field(20)?.parse().ok()?,
+        starttime: field(22)?.parse().ok()?,
+        processor: field(39).and_then(|v| v.parse().ok()),
+    })
+}
+
+/// `USER_HZ`, the unit of the tick counters in `stat`.
+pub(crate) fn clock_ticks_per_sec() -> f64 {
+    match unsafe { libc::sysconf(libc::_SC_CLK_TCK) } {
+        ticks if ticks > 0 => ticks as f64,
+        _ => 100.0,
+    }
+}
+
+/// Human readable name for a `stat` state letter.
+pub(crate) fn state_name(state: char) -> &'static str {
+    match state {
+        'R' => "running",
+        'S' => "sleeping",
+        'D' => "disk_sleep",
+        'T' => "stopped",
+        't' => "tracing_stop",
+        'Z' => "zombie",
+        'X' | 'x' => "dead",
+        'I' => "idle",
+        'P' => "parked",
+        'K' => "wakekill",
+        'W' => "waking",
+        _ => "unknown",
+    }
+}
+
+/// `Key:\tvalue` pairs from `/proc/<pid>/status`.
+pub(crate) struct ProcStatus(HashMap<String, String>);
+
+impl ProcStatus {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(String::as_str)
+    }
+
+    /// First number of the value, e.g. `VmRSS:\t  1234 kB` gives 1234.
+    pub fn get_u64(&self, key: &str) -> Option<u64> {
+        self.get(key)?.split_whitespace().next()?.parse().ok()
+    }
+}
+
+pub(crate) fn read_status(pid: u32) -> Option<ProcStatus> {
+    Some(parse_status(&fs::read_to_string(format!("/proc/{}/status", pid)).ok()?))
+}
+
+/// `/proc/<pid>/smaps_rollup` uses the same `Key: value kB` layout as `status`.
+pub(crate) fn read_smaps_rollup(pid: u32) -> Option<ProcStatus> {
+    Some(parse_status(&fs::read_to_string(format!("/proc/{}/smaps_rollup", pid)).ok()?))
+}
+
+pub(crate) fn parse_status(contents: &str) -> ProcStatus {
+    ProcStatus(
+        contents
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .map(|(key, value)| (key.to_string(), value.trim().to_string()))
+            .collect(),
+    )
+}
+
+/// Turns an encoded `tty_nr` into a device name such as `pts/3` or `tty1`.
+pub(crate) fn tty_name(tty_nr: i32) -> Option<String> {
+    if tty_nr == 0 {
+        return None;
+    }
+    let tty_nr = tty_nr as u32;
+    let major = (tty_nr >> 8) & 0xfff;
+    let minor = (tty_nr & 0xff) | ((tty_nr >> 12) & 0xfff00);
+
+    Some(match major {
+        4 if minor < 64 => format!("tty{}", minor),
+        4 => format!("ttyS{}", minor - 64),
+        136..=143 => format!("pts/{}", (major - 136) * 256 + minor),
+        5 if minor == 0 => "tty".to_string(),
+        5 if minor == 1 => "console".to_string(),
+        _ => format!("{}:{}", major, minor),
+    })
+}
+
+/// System-wide counters from `/proc/stat`. All are totals since boot.
+pub(crate) struct KernelStat {
+    pub context_switches: u64,
+    pub interrupts: u64,
+    pub forks: u64,
+    pub procs_running: u64,
+    pub procs_blocked: u64,
+    /// The aggregate `cpu` line.
+    pub cpu: Option<CpuTicks>,
+}
+
+/// Time spent in each CPU state, in `USER_HZ` ticks. Guest time is already part of `user`.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct CpuTicks {
+    pub user: u64,
+    pub nice: u64,
+    pub system: u64,
+    pub idle: u64,
+    pub iowait: u64,
+    pub irq: u64,
+    pub softirq: u64,
+    pub steal: u64,
+    pub guest: u64,
+}
+
+impl CpuTicks {
+    pub fn total(&self) -> u64 {
+        self.user + self.nice + self.system + self.idle + self.iowait + self.irq + self.softirq + self.steal
+    }
+}
+
+fn parse_cpu_ticks(rest: &str) -> Option<CpuTicks> {
+    let values: Vec<u64> = rest.split_whitespace().map_while(|v| v.parse().ok()).collect();
+    // Older kernels stop after irq/softirq; missing columns read as zero.
+    let field = |n: usize| values.get(n).copied().unwrap_or(0);
+    (values.len() >= 4).then(|| CpuTicks {
+        user: field(0),
+        nice: field(1),
+        system: field(2),
+        idle: field(3),
+        iowait: field(4),
+        irq: field(5),
+        softirq: field(6),
+        steal: field(7),
+        guest: field(8),
+    })
+}
+
+pub(crate) fn read_kernel_stat() -> Option<KernelStat> {
+    parse_kernel_stat(&fs::read_to_string("/proc/stat").ok()?)
+}
+
+pub(crate) fn parse_kernel_stat(contents: &str) -> Option<KernelStat> {
+    // Each line is a key followed by numbers; for `intr` the first number is the total.
+    let value = |key: &str| {
+        contents
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix(' '))
+            .and_then(|rest| rest.split_whitespace().next()?.parse().ok())
+    };
+
+    Some(KernelStat {
+        context_switches: value("ctxt")?,
+        interrupts: value("intr")?,
+        forks: value("processes")?,
+        procs_running: value("procs_running").unwrap_or(0),
+        procs_blocked: value("procs_blocked").unwrap_or(0),
+        cpu: contents
+            .lines()
+            .find_map(|line| line.strip_prefix("cpu "))
+            .and_then(parse_cpu_ticks),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stat_line(comm: &str) -> String {
+        // Fields 23 to 38 are zero; field 39 (processor) is 3.
+        format!("1234 ({}) S 1 1234 1234 34816 1234 4194560 100 0 2 0 15 7 0 0 20 -5 3 0 5000 {} 3 0 0", comm, "0 ".repeat(16).trim_end())
+    }
+
+    #[test]
+    fn stat_fields_are_counted_after_the_last_paren() {
+        let stat = parse_stat(&stat_line("my (odd) ) name")).unwrap();
+        assert_eq!(stat.comm, "my (odd) ) name");
+        assert_eq!(stat.state, 'S');
+        assert_eq!(stat.tty_nr, 34816);
+        assert_eq!((stat.minflt, stat.majflt), (100, 2));
+        assert_eq!((stat.utime, stat.stime), (15, 7));
+        assert_eq!(stat.nice, -5);
+        assert_eq!(stat.num_threads, 3);
+        assert_eq!(stat.starttime, 5000);
+        assert_eq!(stat.processor, Some(3));
+    }
+
+    #[test]
+    fn stat_without_processor_or_comm() {
+        let short = stat_line("sh");
+        let short = &short[..short.find(" 5000").unwrap() + 5];
+        assert_eq!(parse_stat(short).unwrap().processor, None);
+        assert!(parse_stat("1234 sh S 1").is_none());
+        assert!(parse_stat("1234 (sh) S 1 2").is_none());
+    }
+
+    #[test]
+    fn status_values() {
+        let status = parse_status("Name:\tbash\nTgid:\t77\nVmRSS:\t    1234 kB\nGroups:\t\n");
+        assert_eq!(status.get("Name"), Some("bash"));
+        assert_eq!(status.get_u64("Tgid"), Some(77));
+        assert_eq!(status.get_u64("VmRSS"), Some(1234));
+        assert_eq!(status.get("Groups"), Some(""));
+        assert_eq!(status.get_u64("Groups"), None);
+        assert_eq!(status.get("Missing"), None);
+    }
+
+    #[test]
+    fn tty_names() {
+        assert_eq!(tty_name(0), None);
+        assert_eq!(tty_name(34816 + 3).as_deref(), Some("pts/3"));
+        // Minors above 255 are split across the encoding.
+        assert_eq!(tty_name(34816 | 44 | (256 << 12)).as_deref(), Some("pts/300"));
+        assert_eq!(tty_name((4 << 8) | 1).as_deref(), Some("tty1"));
+        assert_eq!(tty_name((4 << 8) | 65).as_deref(), Some("ttyS1"));
+        assert_eq!(tty_name((5 << 8) | 1).as_deref(), Some("console"));
+        assert_eq!(tty_name((8 << 8) | 2).as_deref(), Some("8:2"));
+    }
+
+    #[test]
+    fn kernel_stat() {
+        let stat = parse_kernel_stat(
+            "cpu  100 5 50 1000 20 3 4 2 1 0\ncpu0 100 5 50 1000 20 3 4 2 1 0\nintr 12345 1 2 3\n\
+             ctxt 999\nbtime 1\nprocesses 42\nprocs_running 2\nprocs_blocked 1\n",
+        )
+        .unwrap();
+        assert_eq!((stat.context_switches, stat.interrupts, stat.forks), (999, 12345, 42));
+        assert_eq!((stat.procs_running, stat.procs_blocked), (2, 1));
+        let cpu = stat.cpu.unwrap();
+        assert_eq!((cpu.user, cpu.idle, cpu.steal, cpu.guest), (100, 1000, 2, 1));
+        assert_eq!(cpu.total(), 1184);
+    }
+
+    #[test]
+    fn kernel_stat_from_older_kernels() {
+        let stat = parse_kernel_stat("cpu  1 2 3 4\nintr 5\nctxt 6\nprocesses 7\n").unwrap();
+        assert_eq!((stat.procs_running, stat.procs_blocked), (0, 0));
+        let cpu = stat.cpu.unwrap();
+        assert_eq!((cpu.iowait, cpu.steal), (0, 0));
+        assert_eq!(cpu.total(), 10);
+
+        assert!(parse_kernel_stat("cpu  1 2 3 4\nintr 5\nprocesses 7\n").is_none());
+        assert!(parse_kernel_stat("cpu  1 2 3\nintr 5\nctxt 6\nprocesses 7\n").unwrap().cpu.is_none());
+    }
+}
@@ -0,0 +1,516 @@
+//! A small filter language over `ProcessInfo` fields, e.g.
+//! `cpu > 50 && user == "www" && cmd ~ /node .*server/`.
+//!
+//! Comparisons are `== != > >= < <= ~ !~`, combined with `&& || !` and parentheses.
+//! Values are numbers, quoted strings, `/regex/` (with an optional `i` flag), `true`,
+//! `false` and `null`. A bare field name tests that the field is truthy. On list fields
+//! such as `children` a comparison holds when any element matches.
+
+use regex::{Regex, RegexBuilder};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::cmp::Ordering;
+
+#[derive(Debug, Clone, Serialize, thiserror::Error)]
+#[error("{message}{}", .position.map(|p| format!(" at position {}", p)).unwrap_or_default())]
+pub struct QueryError {
+    message: String,
+    /// Zero-based character offset into the query; `None` when the list options were rejected
+    /// or the query could not be run.
+    position: Option<usize>,
+}
+
+impl QueryError {
+    fn new(message: impl Into<String>, position: usize) -> Self {
+        QueryError { message: message.into(), position: Some(position) }
+    }
+
+    pub(crate) fn unpositioned(message: String) -> Self {
+        QueryError { message, position: None }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CmpOp {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Match,
+    NotMatch,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(f64),
+    Str(String),
+    Regex(String, bool),
+    Cmp(CmpOp),
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+    End,
+}
+
+#[derive(Debug)]
+enum Literal {
+    Number(f64),
+    Str(String),
+    Bool(bool),
+    Null,
+    Regex(Regex),
+}
+
+#[derive(Debug)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare { field: String, position: usize, op: CmpOp, value: Literal },
+    Truthy { field: String, position: usize },
+}
+
+/// A parsed query, ready to be evaluated against many processes.
+#[derive(Debug)]
+pub struct Query {
+    expr: Expr,
+}
+
+impl Query {
+    pub fn parse(source: &str) -> Result<Query, QueryError> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser { tokens, index: 0 };
+        let expr = parser.parse_or()?;
+        match parser.peek() {
+            (Token::End, _) => Ok(Query { expr }),
+            (token, position) => Err(QueryError::new(format!("Unexpected {}", describe(token)), *position)),
+        }
+    }
+
+    /// Rejects field names that `sample` (one serialized process) does not have, so typos
+    /// are reported instead of silently matching nothing.
+    pub fn check_fields(&self, sample: &Map<String, Value>) -> Result<(), QueryError> {
+        fn walk(expr: &Expr, sample: &Map<String, Value>) -> Result<(), QueryError> {
+            match expr {
+                Expr::And(a, b) | Expr::Or(a, b) => {
+                    walk(a, sample)?;
+                    walk(b, sample)
+                }
+                Expr::Not(inner) => walk(inner, sample),
+                Expr::Compare { field, position, .. } | Expr::Truthy { field, position } => {
+                    if sample.contains_key(field) {
+                        Ok(())
+                    } else {
+                        Err(QueryError::new(format!("Unknown field `{}`", field), *position))
+                    }
+                }
+            }
+        }
+        walk(&self.expr, sample)
+    }
+
+    pub fn matches(&self, process: &Map<String, Value>) -> bool {
+        eval(&self.expr, process)
+    }
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Ident(name) => format!("`{}`", name),
+        Token::Number(n) => format!("number {}", n),
+        Token::Str(s) => format!("string {:?}", s),
+        Token::Regex(r, _) => format!("regex /{}/", r),
+        Token::Cmp(_) => "comparison operator".to_string(),
+        Token::And => "`&&`".to_string(),
+        Token::Or => "`||`".to_string(),
+        Token::Not => "`!`".to_string(),
+        Token::LParen => "`(`".to_string(),
+        Token::RParen => "`)`".to_string(),
+        Token::End => "end of query".to_string(),
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, QueryError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = vec![];
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        let next = chars.get(i + 1).copied();
+
+        let token = match c {
+            _ if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '(' => { i += 1; Token::LParen }
+            ')' => { i += 1; Token::RParen }
+            '&' if next == Some('&') => { i += 2; Token::And }
+            '|' if next == Some('|') => { i += 2; Token::Or }
+            '=' if next == Some('=') => { i += 2; Token::Cmp(CmpOp::Eq) }
+            '!' if next == Some('=') => { i += 2; Token::Cmp(CmpOp::Ne) }
+            '!' if next == Some('~') => { i += 2; Token::Cmp(CmpOp::NotMatch) }
+            '!' => { i += 1; Token::Not }
+            '>' if next == Some('=') => { i += 2; Token::Cmp(CmpOp::Ge) }
+            '>' => { i += 1; Token::Cmp(CmpOp::Gt) }
+            '<' if next == Some('=') => { i += 2; Token::Cmp(CmpOp::Le) }
+            '<' => { i += 1; Token::Cmp(CmpOp::Lt) }
+            '~' => { i += 1; Token::Cmp(CmpOp::Match) }
+            '"' | '\'' => {
+                let (value, end) = read_delimited(&chars, i, c)?;
+                i = end;
+                Token::Str(value)
+            }
+            '/' => {
+                let (pattern, end) = read_delimited(&chars, i, '/')?;
+                i = end;
+                let case_insensitive = chars.get(i) == Some(&'i');
+                if case_insensitive {
+                    i += 1;
+                }
+                Token::Regex(pattern, case_insensitive)
+            }
+            _ if c.is_ascii_digit() || (c == '-' && next.is_some_and(|n| n.is_ascii_digit())) => {
+                i += 1;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                let number = text
+                    .parse()
+                    .map_err(|_| QueryError::new(format!("Invalid number `{}`", text), start))?;
+                Token::Number(number)
+            }
+            _ if c.is_alphabetic() || c == '_' => {
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                Token::Ident(chars[start..i].iter().collect())
+            }
+            _ => return Err(QueryError::new(format!("Unexpected character `{}`", c), start)),
+        };
+        tokens.push((token, start));
+    }
+
+    tokens.push((Token::End, chars.len()));
+    Ok(tokens)
+}
+
+/// Reads a string or regex body starting at the opening `delimiter`. A backslash escapes
+/// the delimiter; inside strings it also escapes itself, while regexes keep it verbatim.
+fn read_delimited(chars: &[char], start: usize, delimiter: char) -> Result<(String, usize), QueryError> {
+    let mut value = String::new();
+    let mut i = start + 1;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' if chars.get(i + 1) == Some(&delimiter) => {
+                value.push(delimiter);
+                i += 2;
+            }
+            '\\' if delimiter != '/' && chars.get(i + 1) == Some(&'\\') => {
+                value.push('\\');
+                i += 2;
+            }
+            c if c == delimiter => return Ok((value, i + 1)),
+            c => {
+                value.push(c);
+                i += 1;
+            }
+        }
+    }
+    let what = if delimiter == '/' { "regex" } else { "string" };
+    Err(QueryError::new(format!("Unterminated {}", what), start))
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    index: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &(Token, usize) {
+        &self.tokens[self.index.min(self.tokens.len() - 1)]
+    }
+
+    fn advance(&mut self) -> (Token, usize) {
+        let token = self.peek().clone();
+        self.index += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, QueryError> {
+        let mut left = self.parse_and()?;
+        while self.peek().0 == Token::Or {
+            self.advance();
+            left = Expr::Or(Box::new(left), Box::new(self.parse_and()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, QueryError> {
+        let mut left = self.parse_unary()?;
+        while self.peek().0 == Token::And {
+            self.advance();
+            left = Expr::And(Box::new(left), Box::new(self.parse_unary()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, QueryError> {
+        if self.peek().0 == Token::Not {
+            self.advance();
+            return Ok(Expr::Not(Box::new(self.parse_unary()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, QueryError> {
+        match self.advance() {
+            (Token::LParen, position) => {
+                let expr = self.parse_or()?;
+                match self.advance() {
+                    (Token::RParen, _) => Ok(expr),
+                    (token, at) => Err(QueryError::new(
+                        format!("Expected `)` to close `(` at position {}, found {}", position, describe(&token)),
+                        at,
+                    )),
+                }
+            }
+            (Token::Ident(field), position) => {
+                let op = match self.peek() {
+                    (Token::Cmp(op), _) => *op,
+                    _ => return Ok(Expr::Truthy { field, position }),
+                };
+                self.advance();
+                let value = self.parse_literal(op)?;
+                Ok(Expr::Compare { field, position, op, value })
+            }
+            (token, position) => Err(QueryError::new(
+                format!("Expected a field name or `(`, found {}", describe(&token)),
+                position,
+            )),
+        }
+    }
+
+    fn parse_literal(&mut self, op: CmpOp) -> Result<Literal, QueryError> {
+        let matching = matches!(op, CmpOp::Match | CmpOp::NotMatch);
+        let (token, position) = self.advance();
+        let compile = |pattern: &str, case_insensitive: bool| {
+            RegexBuilder::new(pattern)
+                .case_insensitive(case_insensitive)
+                .build()
+                .map_err(|e| QueryError::new(format!("Invalid regex: {}", e), position))
+        };
+
+        match token {
+            Token::Regex(pattern, case_insensitive) if matching => {
+                Ok(Literal::Regex(compile(&pattern, case_insensitive)?))
+            }
+            Token::Str(pattern) if matching => Ok(Literal::Regex(compile(&pattern, false)?)),
+            _ if matching => Err(QueryError::new(
+                format!("`~` needs a /regex/ or string, found {}", describe(&token)),
+                position,
+            )),
+            Token::Number(n) => Ok(Literal::Number(n)),
+            Token::Str(s) => Ok(Literal::Str(s)),
+            Token::Ident(word) => match word.as_str() {
+                "true" => Ok(Literal::Bool(true)),
+                "false" => Ok(Literal::Bool(false)),
+                "null" => Ok(Literal::Null),
+                _ => Err(QueryError::new(
+                    format!("Expected a value, found `{}` (quote strings)", word),
+                    position,
+                )),
+            },
+            Token::Regex(..) => Err(QueryError::new("Regex values only work with `~` and `!~`", position)),
+            token => Err(QueryError::new(format!("Expected a value, found {}", describe(&token)), position)),
+        }
+    }
+}
+
+fn eval(expr: &Expr, process: &Map<String, Value>) -> bool {
+    match expr {
+        Expr::And(a, b) => eval(a, process) && eval(b, process),
+        Expr::Or(a, b) => eval(a, process) || eval(b, process),
+        Expr::Not(inner) => !eval(inner, process),
+        Expr::Truthy { field, .. } => truthy(process.get(field).unwrap_or(&Value::Null)),
+        Expr::Compare { field, op, value, .. } => {
+            let actual = process.get(field).unwrap_or(&Value::Null);
+            // Negations are "no element matches" on lists, not "some element differs".
+            match op {
+                CmpOp::Ne => !compare(actual, CmpOp::Eq, value),
+                CmpOp::NotMatch => !compare(actual, CmpOp::Match, value),
+                _ => compare(actual, *op, value),
+            }
+        }
+    }
+}
+
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(map) => !map.is_empty(),
+    }
+}
+
+fn compare(actual: &Value, op: CmpOp, expected: &Literal) -> bool {
+    if let Value::Array(items) = actual {
+        return items.iter().any(|item| compare(item, op, expected));
+    }
+
+    let ordering = match (expected, actual) {
+        (Literal::Regex(regex), Value::String(s)) => return regex.is_match(s),
+        (Literal::Regex(regex), Value::Number(n)) => return regex.is_match(&n.to_string()),
+        (Literal::Regex(_), _) => return false,
+        (Literal::Null, _) => return op == CmpOp::Eq && actual.is_null(),
+        (Literal::Bool(b), Value::Bool(a)) => return op == CmpOp::Eq && a == b,
+        (Literal::Number(n), Value::Number(a)) => a.as_f64().and_then(|a| a.partial_cmp(n)),
+        (Literal::Number(n), Value::String(a)) => a.parse::<f64>().ok().and_then(|a| a.partial_cmp(n)),
+        (Literal::Str(s), Value::String(a)) => Some(a.as_str().cmp(s.as_str())),
+        (Literal::Str(s), Value::Number(a)) => {
+            s.parse::<f64>().ok().and_then(|s| a.as_f64().and_then(|a| a.partial_cmp(&s)))
+        }
+        _ => None,
+    };
+
+    let Some(ordering) = ordering else {
+        return false;
+    };
+    match op {
+        CmpOp::Eq => ordering == Ordering::Equal,
+        CmpOp::Gt => ordering == Ordering::Greater,
+        CmpOp::Ge => ordering != Ordering::Less,
+        CmpOp::Lt => ordering == Ordering::Less,
+        CmpOp::Le => ordering != Ordering::Greater,
+        CmpOp::Ne | CmpOp::Match | CmpOp::NotMatch => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn process() -> Map<String, Value> {
+        match json!({
+            "pid": 42,
+            "name": "nginx",
+            "cmd": "nginx: worker process",
+            "cpu": 12.5,
+            "user": "www",
+            "tty": null,
+            "children": [43, 44],
+            "cmd_raw": ["nginx", "-g", "daemon off;"],
+        }) {
+            Value::Object(map) => map,
+            _ => unreachable!(),
+        }
+    }
+
+    fn matches(query: &str) -> bool {
+        Query::parse(query).unwrap().matches(&process())
+    }
+
+    fn error(query: &str) -> (String, Option<usize>) {
+        let e = Query::parse(query).unwrap_err();
+        (e.message, e.position)
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        assert!(matches(r#"pid == 42 || pid == 1 && user == "root""#));
+        assert!(!matches(r#"(pid == 42 || pid == 1) && user == "root""#));
+        assert!(matches(r#"user == "root" && pid == 1 || cpu > 10"#));
+    }
+
+    #[test]
+    fn not_applies_to_the_next_operand() {
+        assert!(matches("!cpu > 50"));
+        assert!(!matches("!cpu > 10 && pid == 42"));
+        assert!(matches("!(cpu > 10 && pid == 1)"));
+        assert!(matches("!!name"));
+        assert!(matches("!tty"));
+    }
+
+    #[test]
+    fn comparisons() {
+        assert!(matches("cpu >= 12.5 && cpu <= 12.5 && cpu < 13 && cpu > -1"));
+        assert!(matches(r#"name == 'nginx' && name != "apache""#));
+        assert!(matches("cmd ~ /WORKER/i && name !~ /^apache/"));
+        assert!(matches(r#"cmd ~ "worker\\s+process""#));
+        assert!(matches("tty == null && user != null"));
+    }
+
+    #[test]
+    fn lists_match_any_element() {
+        assert!(matches("children == 44"));
+        assert!(matches("children > 43"));
+        assert!(!matches("children > 44"));
+        assert!(matches("cmd_raw ~ /^daemon/"));
+    }
+
+    #[test]
+    fn negations_on_lists_mean_no_element_matches() {
+        assert!(!matches("children != 43"));
+        assert!(matches("children != 45"));
+        assert!(!matches("cmd_raw !~ /^-g$/"));
+        assert!(matches("cmd_raw !~ /^-d$/"));
+    }
+
+    #[test]
+    fn strings_and_regexes_escape_their_delimiter() {
+        let query = Query::parse(r#"name == "a\"b\\c" || cmd ~ /a\/b\d/"#).unwrap();
+        match query.expr {
+            Expr::Or(a, b) => {
+                assert!(matches!(*a, Expr::Compare { value: Literal::Str(ref s), .. } if s == r#"a"b\c"#));
+                assert!(matches!(*b, Expr::Compare { value: Literal::Regex(ref r), .. } if r.as_str() == r"a/b\d"));
+            }
+            expr => panic!("unexpected {:?}", expr),
+        }
+    }
+
+    #[test]
+    fn unterminated_strings_and_regexes() {
+        assert_eq!(error(r#"name == "nginx"#), ("Unterminated string".to_string(), Some(8)));
+        assert_eq!(error("name == 'ng\\'"), ("Unterminated string".to_string(), Some(8)));
+        assert_eq!(error("cmd ~ /worker"), ("Unterminated regex".to_string(), Some(6)));
+    }
+
+    #[test]
+    fn error_positions() {
+        assert_eq!(error("cpu > ").1, Some(6));
+        assert_eq!(error("cpu >> 5").1, Some(5));
+        assert_eq!(error("cpu @ 5"), ("Unexpected character `@`".to_string(), Some(4)));
+        assert_eq!(error("(cpu > 5").1, Some(8));
+        assert_eq!(error("cpu > 1 cpu"), ("Unexpected `cpu`".to_string(), Some(8)));
+        assert_eq!(error("name == nginx"), ("Expected a value, found `nginx` (quote strings)".to_string(), Some(8)));
+        assert_eq!(error("cpu > /5/").1, Some(6));
+        assert_eq!(error("cmd ~ /(/").1, Some(6));
+        assert_eq!(error("&& cpu").1, Some(0));
+        // Positions count characters, not bytes.
+        assert_eq!(error(r#"name == "é" && @"#).1, Some(15));
+    }
+
+    #[test]
+    fn unknown_fields_are_reported_with_their_position() {
+        let query = Query::parse(r#"cpu > 1 && (nmae == "x" || pid)"#).unwrap();
+        let e = query.check_fields(&process()).unwrap_err();
+        assert_eq!(e.to_string(), "Unknown field `nmae` at position 12");
+    }
+
+    #[test]
+    fn display_omits_a_missing_position() {
+        assert_eq!(QueryError::unpositioned("Unknown process field 'x'".to_string()).to_string(), "Unknown process field 'x'");
+    }
+}
@@ -0,0 +1,265 @@
+#[cfg(target_os = "linux")]
+use std::collections::{HashMap, HashSet};
+use serde::Serialize;
+
+#[derive(Serialize, Clone)]
+pub struct SocketOwner {
+    pub(crate) pid: u32,
+    pub(crate) name: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct SocketInfo {
+    pub(crate) protocol: String,
+    pub(crate) local_address: String,
+    pub(crate) local_port: Option<u16>,
+    pub(crate) remote_address: String,
+    pub(crate) remote_port: Option<u16>,
+    pub(crate) state: String,
+    pub(crate) inode: u64,
+    pub(crate) uid: Option<u32>,
+    pub(crate) owners: Vec<SocketOwner>,
+}
+
+#[cfg(target_os = "linux")]
+impl SocketInfo {
+    /// TCP sockets accepting connections and UDP sockets bound without a peer.
+    pub(crate) fn is_listening(&self) -> bool {
+        self.state == "LISTEN" || (self.protocol.starts_with("udp") && self.state == "UNCONN")
+    }
+}
+
+/// Sockets owned by `pid`, matched through the inodes of its `socket:[...]` descriptors.
+#[tauri::command]
+pub async fn process_sockets(pid: u32) -> Result<Vec<SocketInfo>, String> {
+    #[cfg(target_os = "linux")]
+    {
+        tauri::async_runtime::spawn_blocking(move || {
+            let inodes = socket_inodes(pid)
+                .map_err(|e| format!("Failed to read descriptors of PID {}: {}", pid, e))?;
+            let owners = HashMap::from_iter(inodes.iter().map(|inode| (*inode, vec![pid])));
+            let mut sockets: Vec<SocketInfo> = read_all_sockets()
+                .into_iter()
+                .filter(|s| inodes.contains(&s.inode))
+                .collect();
+            attach_owners(&mut sockets, &owners);
+            Ok(sockets)
+        }).await.map_err(|e| e.to_string())?
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = pid;
+        Err("Unsupported platform".to_string())
+    }
+}
+
+/// System-wide list of listening TCP and bound UDP sockets with the processes holding them.
+#[tauri::command]
+pub async fn listening_ports() -> Result<Vec<SocketInfo>, String> {
+    #[cfg(target_os = "linux")]
+    {
+        tauri::async_runtime::spawn_blocking(|| {
+            let mut sockets: Vec<SocketInfo> = read_inet_sockets()
+                .into_iter()
+                .filter(SocketInfo::is_listening)
+                .collect();
+            attach_owners(&mut sockets, &socket_owners());
+            sockets.sort_by(|a, b| (a.local_port, &a.protocol).cmp(&(b.local_port, &b.protocol)));
+            Ok(sockets)
+        }).await.map_err(|e| e.to_string())?
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        Err("Unsupported platform".to_string())
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn read_all_sockets() -> Vec<SocketInfo> {
+    let mut sockets = read_inet_sockets();
+    sockets.extend(read_unix_sockets());
+    sockets
+}
+
+/// TCP and UDP sockets over IPv4 and IPv6 from `/proc/net`, without owners.
+#[cfg(target_os = "linux")]
+pub(crate) fn read_inet_sockets() -> Vec<SocketInfo> {
+    ["tcp", "tcp6", "udp", "udp6"]
+        .iter()
+        .flat_map(|protocol| {
+            let table = std::fs::read_to_string(format!("/proc/net/{}", protocol)).unwrap_or_default();
+            table
+                .lines()
+                .skip(1)
+                .filter_map(|line| parse_inet_line(protocol, line))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn parse_inet_line(protocol: &str, line: &str) -> Option<SocketInfo> {
+    // sl local_address rem_address st tx_queue:rx_queue tr:tm->when retrnsmt uid timeout inode
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let (local_address, local_port) = parse_address(fields.get(1)?)?;
+    let (remote_address, remote_port) = parse_address(fields.get(2)?)?;
+    let state = u8::from_str_radix(fields.get(3)?, 16).ok()?;
+
+    Some(SocketInfo {
+        protocol: protocol.to_string(),
+        local_address,
+        local_port: Some(local_port),
+        remote_address,
+        remote_port: Some(remote_port),
+        state: inet_state_name(protocol, state).to_string(),
+        inode: fields.get(9)?.parse().ok()?,
+        uid: fields.get(7).and_then(|u| u.parse().ok()),
+        owners: vec![],
+    })
+}
+
+/// Decodes `0100007F:1F90` style addresses. The address words are printed in host byte
+/// order while the port is already a plain number.
+#[cfg(target_os = "linux")]
+fn parse_address(field: &str) -> Option<(String, u16)> {
+    let (address, port) = field.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+
+    let mut bytes = Vec::with_capacity(16);
+    for chunk in address.as_bytes().chunks(8) {
+        let word = u32::from_str_radix(std::str::from_utf8(chunk).ok()?, 16).ok()?;
+        bytes.extend_from_slice(&word.to_ne_bytes());
+    }
+
+    let address = match bytes.len() {
+        4 => std::net::Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3]).to_string(),
+        16 => {
+            let octets: [u8; 16] = bytes.try_into().ok()?;
+            let v6 = std::net::Ipv6Addr::from(octets);
+            match v6.to_ipv4_mapped() {
+                Some(v4) => v4.to_string(),
+                None => v6.to_string(),
+            }
+        }
+        _ => return None,
+    };
+    Some((address, port))
+}
+
+#[cfg(target_os = "linux")]
+fn inet_state_name(protocol: &str, state: u8) -> &'static str {
+    match state {
+        0x01 => "ESTABLISHED",
+        0x02 => "SYN_SENT",
+        0x03 => "SYN_RECV",
+        0x04 => "FIN_WAIT1",
+        0x05 => "FIN_WAIT2",
+        0x06 => "TIME_WAIT",
+        // UDP reuses TCP_CLOSE for sockets that are bound but not connected.
+        0x07 if protocol.starts_with("udp") => "UNCONN",
+        0x07 => "CLOSE",
+        0x08 => "CLOSE_WAIT",
+        0x09 => "LAST_ACK",
+        0x0A => "LISTEN",
+        0x0B => "CLOSING",
+        0x0C => "NEW_SYN_RECV",
+        _ => "UNKNOWN",
+    }
+}
+
+/// Unix domain sockets from `/proc/net/unix`. The path goes into `local_address`.
+#[cfg(target_os = "linux")]
+pub(crate) fn read_unix_sockets() -> Vec<SocketInfo> {
+    let table = std::fs::read_to_string("/proc/net/unix").unwrap_or_default();
+    table
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            // Num RefCount Protocol Flags Type St Inode Path
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let flags = u32::from_str_radix(fields.get(3)?, 16).ok()?;
+            let state = u8::from_str_radix(fields.get(5)?, 16).ok()?;
+            const SO_ACCEPTCON: u32 = 0x10000;
+
+            Some(SocketInfo {
+                protocol: "unix".to_string(),
+                local_address: fields.get(7..).map(|p| p.join(" ")).unwrap_or_default(),
+                local_port: None,
+                remote_address: String::new(),
+                remote_port: None,
+                state: match state {
+                    _ if flags & SO_ACCEPTCON != 0 => "LISTEN",
+                    0x01 => "UNCONNECTED",
+                    0x02 => "CONNECTING",
+                    0x03 => "CONNECTED",
+                    0x04 => "DISCONNECTING",
+                    _ => "UNKNOWN",
+                }.to_string(),
+                inode: fields.get(6)?.parse().ok()?,
+                uid: None,
+                owners: vec![],
+            })
+        })
+        .collect()
+}
+
+/// Inodes of every `socket:[inode]` descriptor held by `pid`.
+#[cfg(target_os = "linux")]
+pub(crate) fn socket_inodes(pid: u32) -> std::io::Result<HashSet<u64>> {
+    Ok(std::fs::read_dir(format!("/proc/{}/fd", pid))?
+        .flatten()
+        .filter_map(|entry| {
+            let target = std::fs::read_link(entry.path()).ok()?;
+            target.to_str()?.strip_prefix("socket:[")?.strip_suffix(']')?.parse().ok()
+        })
+        .collect())
+}
+
+/// Socket inode -> PIDs holding it. A socket is shared by every process that inherited it,
+/// so one inode can map to several PIDs.
+#[cfg(target_os = "linux")]
+pub(crate) fn socket_owners() -> HashMap<u64, Vec<u32>> {
+    let mut owners: HashMap<u64, Vec<u32>> = HashMap::new();
+    let Ok(proc_dir) = std::fs::read_dir("/proc") else {
+        return owners;
+    };
+    for entry in proc_dir.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|n| n.parse::<u32>().ok()) else {
+            continue;
+        };
+        // Other users' descriptors are unreadable without privileges; skip them quietly.
+        let Ok(inodes) = socket_inodes(pid) else {
+            continue;
+        };
+        for inode in inodes {
+            owners.entry(inode).or_default().push(pid);
+        }
+    }
+    owners
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn attach_owners(sockets: &mut [SocketInfo], owners: &HashMap<u64, Vec<u32>>) {
+    let mut names: HashMap<u32, String> = HashMap::new();
+    for socket in sockets.iter_mut() {
+        let Some(pids) = owners.get(&socket.inode) else {
+            continue;
+        };
+        socket.owners = pids
+            .iter()
+            .map(|pid| SocketOwner {
+                pid: *pid,
+                name: names
+                    .entry(*pid)
+                    .or_insert_with(|| {
+                        std::fs::read(format!("/proc/{}/comm", pid))
+                            .map(|comm| String::from_utf8_lossy(&comm).trim_end().to_string())
+                            .unwrap_or_default()
+                    })
+                    .clone(),
+            })
+            .collect();
+    }
+}
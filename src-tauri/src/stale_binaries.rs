@@ -0,0 +1,152 @@
+#[cfg(target_os = "linux")]
+use std::collections::{BTreeMap, HashMap, HashSet};
+use serde::Serialize;
+
+#[derive(Serialize, Clone)]
+pub struct StaleProcess {
+    pid: u32,
+    name: String,
+    cmd: String,
+    parent_pid: Option<u32>,
+    exe_deleted: bool,
+    exe_replaced: bool,
+    stale_libraries: Vec<String>,
+}
+
+/// Processes running the same executable. `restart_pids` are the members whose parent is not
+/// in the group, so restarting them with `kill_children` covers the whole group.
+#[derive(Serialize, Clone)]
+pub struct StaleGroup {
+    exe: String,
+    pids: Vec<u32>,
+    restart_pids: Vec<u32>,
+    processes: Vec<StaleProcess>,
+}
+
+/// Finds processes whose executable or mapped shared libraries were deleted or replaced on
+/// disk since they started, typically by a package upgrade.
+#[tauri::command]
+pub async fn scan_stale_processes() -> Result<Vec<StaleGroup>, String> {
+    #[cfg(target_os = "linux")]
+    {
+        tauri::async_runtime::spawn_blocking(scan_stale_processes_linux)
+            .await
+            .map_err(|e| e.to_string())?
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        Err("Unsupported platform".to_string())
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn scan_stale_processes_linux() -> Result<Vec<StaleGroup>, String> {
+    use std::os::unix::fs::MetadataExt;
+    use crate::memory_maps::{is_shared_library, read_maps};
+
+    // File currently at each path, as seen from a process's root and mount namespace, shared
+    // across processes with the same view and libraries.
+    let mut on_disk: HashMap<(String, String), Option<(u64, u64)>> = HashMap::new();
+
+    let processes = crate::list_process::current_processes();
+    // sysinfo lists threads as processes on Linux; they share the leader's exe and maps.
+    let threads = crate::list_process::with_refreshed_sys(crate::list_process::thread_ids);
+
+    let mut groups: BTreeMap<String, Vec<StaleProcess>> = BTreeMap::new();
+    for process in processes {
+        if threads.contains(&process.pid) {
+            continue;
+        }
+        let proc_exe = format!("/proc/{}/exe", process.pid);
+        // Kernel threads have no executable; other users' processes are unreadable without privileges.
+        let Ok(exe_link) = std::fs::read_link(&proc_exe) else {
+            continue;
+        };
+        // Paths in `exe` and `maps` are relative to the process's root, which differs from ours
+        // inside containers and chroots.
+        let root = format!("/proc/{}/root", process.pid);
+        let (Ok(mount_ns), Ok(root_meta)) = (
+            std::fs::read_link(format!("/proc/{}/ns/mnt", process.pid)),
+            std::fs::metadata(&root),
+        ) else {
+            continue;
+        };
+        let view = format!("{}:{}:{}", mount_ns.display(), root_meta.dev(), root_meta.ino());
+        let mut file_at = |path: &str| -> Option<(u64, u64)> {
+            *on_disk
+                .entry((view.clone(), path.to_string()))
+                .or_insert_with(|| std::fs::metadata(format!("{}{}", root, path)).ok().map(|m| file_id(&m)))
+        };
+
+        let exe_link = exe_link.to_string_lossy().into_owned();
+        let exe_deleted = exe_link.ends_with(" (deleted)");
+        let exe = exe_link.strip_suffix(" (deleted)").unwrap_or(&exe_link).to_string();
+        // stat() through /proc/<pid>/exe reaches the file that is actually running.
+        let running = std::fs::metadata(&proc_exe).ok().map(|m| file_id(&m));
+        let exe_replaced = !exe_deleted && running.is_some() && running != file_at(&exe);
+
+        let mut stale_libraries = HashSet::new();
+        for region in read_maps(process.pid).unwrap_or_default() {
+            let Some(path) = region.path.as_deref() else {
+                continue;
+            };
+            if !is_shared_library(path) {
+                continue;
+            }
+            let mapped = parse_device(&region.device).map(|device| (device, region.inode));
+            if region.deleted || (mapped.is_some() && file_at(path) != mapped) {
+                stale_libraries.insert(path.to_string());
+            }
+        }
+
+        if !exe_deleted && !exe_replaced && stale_libraries.is_empty() {
+            continue;
+        }
+        let mut stale_libraries: Vec<String> = stale_libraries.into_iter().collect();
+        stale_libraries.sort();
+
+        groups.entry(exe).or_default().push(StaleProcess {
+            pid: process.pid,
+            name: process.name,
+            cmd: process.cmd,
+            parent_pid: process.parent_pid,
+            exe_deleted,
+            exe_replaced,
+            stale_libraries,
+        });
+    }
+
+    Ok(groups
+        .into_iter()
+        .map(|(exe, mut processes)| {
+            processes.sort_by_key(|p| p.pid);
+            let pids: Vec<u32> = processes.iter().map(|p| p.pid).collect();
+            let restart_pids = processes
+                .iter()
+                .filter(|p| p.parent_pid.is_none_or(|parent| !pids.contains(&parent)))
+                .map(|p| p.pid)
+                .collect();
+            StaleGroup { exe, pids, restart_pids, processes }
+        })
+        .collect())
+}
+
+/// Device and inode of a file, with the device as `(major << 32) | minor` like [`parse_device`].
+#[cfg(target_os = "linux")]
+fn file_id(meta: &std::fs::Metadata) -> (u64, u64) {
+    use std::os::unix::fs::MetadataExt;
+
+    // glibc's encoding of dev_t, see gnu_dev_major/gnu_dev_minor.
+    let dev = meta.dev();
+    let major = ((dev >> 8) & 0xfff) | ((dev >> 32) & !0xfff);
+    let minor = (dev & 0xff) | ((dev >> 12) & !0xff);
+    ((major << 32) | minor, meta.ino())
+}
+
+/// The `major:minor` device column of `/proc/<pid>/maps`, in hex.
+#[cfg(target_os = "linux")]
+fn parse_device(device: &str) -> Option<u64> {
+    let (major, minor) = device.split_once(':')?;
+    Some((u64::from_str_radix(major, 16).ok()? << 32) | u64::from_str_radix(minor, 16).ok()?)
+}
@@ -0,0 +1,165 @@
+use std::sync::Mutex;
+use std::thread;
+#[cfg(target_os = "linux")]
+use std::time::Instant;
+use lazy_static::lazy_static;
+use serde::Serialize;
+use sysinfo::{System, ThreadKind};
+use crate::list_process::{now_ms, sampler_running, with_refreshed_sys};
+
+#[derive(Serialize, Clone)]
+pub struct CpuCore {
+    name: String,
+    usage: f32,
+    frequency_mhz: u64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct LoadAverage {
+    one: f64,
+    five: f64,
+    fifteen: f64,
+}
+
+/// Host totals taken from the same SYS refresh as the process list. Memory and swap are
+/// KiB like `ProcessInfo.memory`; the `/proc/stat` rates are per second since the previous
+/// sample and `None` on the first one or off Linux.
+#[derive(Serialize, Clone)]
+pub struct SystemOverview {
+    timestamp_ms: u64,
+    cpu_usage: f32,
+    cores: Vec<CpuCore>,
+    physical_cores: Option<usize>,
+    load_average: LoadAverage,
+    memory_total: u64,
+    memory_used: u64,
+    memory_available: u64,
+    memory_free: u64,
+    swap_total: u64,
+    swap_used: u64,
+    swap_free: u64,
+    uptime_secs: u64,
+    boot_time_secs: u64,
+    process_count: usize,
+    thread_count: Option<usize>,
+    procs_running: Option<u64>,
+    procs_blocked: Option<u64>,
+    context_switches_per_sec: Option<f64>,
+    interrupts_per_sec: Option<f64>,
+    forks_per_sec: Option<f64>,
+}
+
+/// `/proc/stat` counters of the previous sample, for turning totals into rates.
+#[cfg(target_os = "linux")]
+#[derive(Clone, Copy)]
+struct KernelCounters {
+    at: Instant,
+    context_switches: u64,
+    interrupts: u64,
+    forks: u64,
+}
+
+lazy_static! {
+    static ref LAST_OVERVIEW: Mutex<Option<SystemOverview>> = Mutex::new(None);
+}
+
+#[cfg(target_os = "linux")]
+lazy_static! {
+    static ref PREVIOUS_COUNTERS: Mutex<Option<KernelCounters>> = Mutex::new(None);
+}
+
+#[cfg(target_os = "linux")]
+fn per_sec(now: u64, before: u64, secs: f64) -> Option<f64> {
+    (secs > 0.0).then(|| now.saturating_sub(before) as f64 / secs)
+}
+
+/// Builds an overview from an already refreshed `sys` and advances the rate baseline.
+pub(crate) fn sample_overview(sys: &System) -> SystemOverview {
+    let load = System::load_average();
+    // sysinfo lists threads as entries of their own on Linux, so only count real processes.
+    let process_count = sys
+        .processes()
+        .values()
+        .filter(|p| p.thread_kind() != Some(ThreadKind::Userland))
+        .count();
+
+    #[cfg_attr(not(target_os = "linux"), allow(unused_mut))]
+    let mut overview = SystemOverview {
+        timestamp_ms: now_ms(),
+        cpu_usage: sys.global_cpu_usage(),
+        cores: sys
+            .cpus()
+            .iter()
+            .map(|cpu| CpuCore {
+                name: cpu.name().to_string(),
+                usage: cpu.cpu_usage(),
+                frequency_mhz: cpu.frequency(),
+            })
+            .collect(),
+        physical_cores: System::physical_core_count(),
+        load_average: LoadAverage { one: load.one, five: load.five, fifteen: load.fifteen },
+        memory_total: sys.total_memory() / 1024,
+        memory_used: sys.used_memory() / 1024,
+        memory_available: sys.available_memory() / 1024,
+        memory_free: sys.free_memory() / 1024,
+        swap_total: sys.total_swap() / 1024,
+        swap_used: sys.used_swap() / 1024,
+        swap_free: sys.free_swap() / 1024,
+        uptime_secs: System::uptime(),
+        boot_time_secs: System::boot_time(),
+        process_count,
+        thread_count: None,
+        procs_running: None,
+        procs_blocked: None,
+        context_switches_per_sec: None,
+        interrupts_per_sec: None,
+        forks_per_sec: None,
+    };
+
+    #[cfg(target_os = "linux")]
+    {
+        overview.thread_count = Some(sys.processes().len());
+
+        if let Some(stat) = crate::procfs::read_kernel_stat() {
+            let now = KernelCounters {
+                at: Instant::now(),
+                context_switches: stat.context_switches,
+                interrupts: stat.interrupts,
+                forks: stat.forks,
+            };
+            let mut previous = PREVIOUS_COUNTERS.lock().unwrap();
+            if let Some(before) = previous.replace(now) {
+                let secs = now.at.duration_since(before.at).as_secs_f64();
+                overview.context_switches_per_sec = per_sec(now.context_switches, before.context_switches, secs);
+                overview.interrupts_per_sec = per_sec(now.interrupts, before.interrupts, secs);
+                overview.forks_per_sec = per_sec(now.forks, before.forks, secs);
+            }
+            overview.procs_running = Some(stat.procs_running);
+            overview.procs_blocked = Some(stat.procs_blocked);
+        }
+    }
+
+    *LAST_OVERVIEW.lock().unwrap() = Some(overview.clone());
+    overview
+}
+
+/// Host CPU, memory, load and kernel activity counters. Without the sampler the first call
+/// waits briefly so CPU usage and rates cover a real interval.
+#[tauri::command]
+pub async fn system_overview() -> Result<SystemOverview, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        if sampler_running() {
+            if let Some(overview) = LAST_OVERVIEW.lock().unwrap().clone() {
+                return overview;
+            }
+        }
+
+        let first = LAST_OVERVIEW.lock().unwrap().is_none();
+        let overview = with_refreshed_sys(sample_overview);
+        if !first {
+            return overview;
+        }
+        thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+        with_refreshed_sys(sample_overview)
+    }).await.map_err(|e| e.to_string())
+}
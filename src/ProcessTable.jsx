@@ -16,7 +16,7 @@ import {
 
 import {
     ActionDropdown, Actions_ICON,
-    Alert,
+    Alert, applyProcessDelta,
     BatchActionsDropdown, Close_ICON, colorCpu, colorMem, formatBytes,
     killProcess, Log_ICON, parseBytes,
     PriorityDialog, Respawn_ICON, RespawnListDropdown, SelectAll_ICON, SortableHeader, Tooltip_,
@@ -55,19 +55,60 @@ export default function ProcessTable({alertVisible,alertMessage,setAlertMessage,
         setTimeout(() => setAlertVisible(false), 5000);
     };
     const toggleExpanded = (pid) => {
+        if (!expandedPids.includes(pid)) {
+            invoke("process_history", { pid, windowSecs: 60, maxPoints: 20 })
+                .then((history) => {
+                    setHistoryMap((prev) => {
+                        const next = new Map(prev);
+                        next.set(pid, {
+                            cpuData: history.points.map(p => p.cpu),
+                            ramData: history.points.map(p => p.memory / 1024),
+                            labels: history.points.map(p => new Date(p.timestamp_ms).toLocaleTimeString()),
+                        });
+                        return next;
+                    });
+                })
+                .catch(() => {});
+        }
         setExpandedPids((prev) =>
             prev.includes(pid) ? prev.filter((id) => id !== pid) : [...prev, pid]
         );
     };
     useEffect(() => {
-        const fetchProcesses = async () => {
-            const procs = await invoke("list_processes");
-            setProcesses(procs);
-
+        let seq = null;
+        const resync = async () => {
+            try {
+                const snapshot = await invoke("resync_processes");
+                // A snapshot event may have arrived while this was in flight.
+                if (seq !== null && snapshot.seq <= seq) return;
+                seq = snapshot.seq;
+                setProcesses(snapshot.processes);
+            } catch (e) {
+                seq = null;
+            }
+        };
+        const unlistenSnapshot = listen("process_snapshot", (event) => {
+            seq = event.payload.seq;
+            setProcesses(event.payload.processes);
+        });
+        const unlistenDelta = listen("process_delta", (event) => {
+            const delta = event.payload;
+            if (seq === null || delta.base_seq !== seq) {
+                resync();
+                return;
+            }
+            seq = delta.seq;
+            setProcesses(prev => applyProcessDelta(prev, delta));
+        });
+        // Seeded from the stream only; before the sampler's first tick there is nothing to
+        // resync and the first process_snapshot event fills the table.
+        const subscription = invoke("subscribe_processes", { intervalMs: 2000 });
+        subscription.then(resync);
+        return () => {
+            unlistenSnapshot.then(f => f());
+            unlistenDelta.then(f => f());
+            subscription.then(id => invoke("unsubscribe_processes", { subscriptionId: id }));
         };
-        fetchProcesses();
-        const interval = setInterval(fetchProcesses, 2000);
-        return () => clearInterval(interval);
     }, []);
 
     useEffect(() => {
@@ -264,12 +305,8 @@ export default function ProcessTable({alertVisible,alertMessage,setAlertMessage,
             showAlert("No processes selected.")
             return
         };
-        const args = [];
         for (const pid of pids) {
-            const exePath = (await invoke("pid_to_proc", { pid: pid })).exe;
-            if (!exePath) return;
-
-            await invoke("auto_respawn", { pid, exePath, args, checkInterval: 2, restartDelay: 3, maxRestarts: 777 });
+            await invoke("auto_respawn", { pid, checkInterval: 2, restartDelay: 3, maxRestarts: 777 });
         }
         setAutoRespawns(prev => [...new Set([...prev, ...pids])]);
         showAlert(`Started auto-respawn on ${pids.length} processes.`);
@@ -282,6 +319,10 @@ export default function ProcessTable({alertVisible,alertMessage,setAlertMessage,
         localStorage.setItem('chartSettings', JSON.stringify(settings));
     }, [settings]);
 
+    useEffect(() => {
+        invoke("set_sample_pss", { enabled: !!settings.pss });
+    }, [settings.pss]);
+
     const toggleSetting = (key) => {
         setSettings((prev) => ({
             ...prev,
@@ -477,6 +518,18 @@ export default function ProcessTable({alertVisible,alertMessage,setAlertMessage,
                         sortConfig={sortConfig}
                     />
                     }
+                    {
+                        settings.pss &&
+                    <SortableHeader
+                        extra_class={" w-20 px-2 "}
+                        title={
+                            <Tooltip_ text="Proportional Set Size (MB)">PSS</Tooltip_>
+                        }
+                        sortKey="pss"
+                        requestSort={requestSort}
+                        sortConfig={sortConfig}
+                    />
+                    }
                     {
                         settings.prio &&
                     <SortableHeader
@@ -561,6 +614,10 @@ export default function ProcessTable({alertVisible,alertMessage,setAlertMessage,
                                 settings.mem &&
                             <td className={" text-center py-2 " + colorMem(proc.memory)}>{(proc.memory / 1024).toFixed(1)}</td>
                             }
+                            {
+                                settings.pss &&
+                            <td className={" text-center py-2 " + colorMem(proc.pss ?? 0)}>{proc.pss == null ? "-" : (proc.pss / 1024).toFixed(1)}</td>
+                            }
                             {
                                 settings.prio &&
                                 <td className={"py-2"}>{proc.priority}</td>
@@ -7,6 +7,7 @@ const defaultSettings = {
     twb: true,
     cpu: true,
     mem: true,
+    pss: false,
     prio: true,
     ar: true,
 };
@@ -51,6 +52,7 @@ const settingLabels = {
     twb: 'TWB',
     cpu: 'CPU',
     mem: 'Memory',
+    pss: 'PSS',
     prio: 'Priority',
     ar: 'AR',
 };
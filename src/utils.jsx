@@ -31,15 +31,23 @@ export const colorMem = (mem) => {
     return 'text-green-600';
 };
 
+export function applyProcessDelta(processes, delta) {
+    // Added rows replace any row with the same pid, so a replayed delta can't duplicate it.
+    const removed = new Set([...delta.removed, ...delta.added.map(p => p.pid)]);
+    const changed = new Map(delta.changed.map(c => [c.pid, c]));
+    const next = processes
+        .filter(p => !removed.has(p.pid))
+        .map(p => changed.has(p.pid) ? { ...p, ...changed.get(p.pid) } : p);
+    return [...next, ...delta.added];
+}
+
 export async function killProcess(pid) {
     await invoke("kill_process", { pid, killChildren: true, timeoutSecs: 5 });
 }
 
 
 export async function killAndRestart(proc) {
-    const exePath = proc.exe;
-    const args = [];
-    await invoke("kill_and_restart", { pid: proc.pid, killChildren: true, timeoutSecs: 5, exePath, args });
+    await invoke("kill_and_restart", { pid: proc.pid, killChildren: true, timeoutSecs: 5 });
 }
 
 
@@ -49,9 +57,7 @@ export async function autoRespawnHandler(proc, autoRespawns, setAutoRespawns) {
         await invoke("stop_auto_respawn", { pid: proc.pid });
         setAutoRespawns(prev => prev.filter(p => p !== proc.pid));
     } else {
-        const exePath = proc.exe;
-        const args = [];
-        await invoke("auto_respawn", { pid: proc.pid, exePath, args, checkInterval: 2, restartDelay: 3, maxRestarts: 777 });
+        await invoke("auto_respawn", { pid: proc.pid, checkInterval: 2, restartDelay: 3, maxRestarts: 777 });
         setAutoRespawns(prev => [...new Set([...prev, proc.pid])]);
     }
 }